@@ -0,0 +1,118 @@
+use crate::schema::{Column, Table};
+use crate::sql::{comment_text, escape_sql_string, quote_ident, quote_table};
+use anyhow::Result;
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+
+// ====================================================================================
+// SCHEMA RECONSTRUCTION
+// ====================================================================================
+
+/// Writes `CREATE TABLE` statements for `tables` (already in dependency order), followed by
+/// the foreign keys as `ALTER TABLE` so cyclic references still apply cleanly. Enum types the
+/// columns use are created first; domains and other user types must exist in the target, as
+/// must `btree_gist` when an `EXCLUDE` constraint compares plain scalars.
+pub fn write_schema(writer: &mut impl Write, tables: &[Table]) -> Result<()> {
+    let schemas: BTreeSet<&str> = tables.iter()
+        .map(|t| t.schema_name.as_str())
+        .filter(|s| *s != "public")
+        .collect();
+    for schema in schemas {
+        writeln!(writer, "CREATE SCHEMA IF NOT EXISTS {};", quote_ident(schema))?;
+    }
+    writeln!(writer)?;
+
+    // `sql_type` is `format_type`'s already-quoted name; enum[] columns share their element's type.
+    let enums: BTreeMap<&str, &[String]> = tables.iter()
+        .filter(|t| !t.is_view)
+        .flat_map(|t| t.columns.iter())
+        .filter(|c| !c.enum_labels.is_empty())
+        .map(|c| (c.sql_type.trim_end_matches("[]"), c.enum_labels.as_slice()))
+        .collect();
+    for (name, labels) in &enums {
+        let labels: Vec<String> = labels.iter().map(|l| escape_sql_string(l)).collect();
+        writeln!(writer, "CREATE TYPE {} AS ENUM ({});", name, labels.join(", "))?;
+    }
+    if !enums.is_empty() {
+        writeln!(writer)?;
+    }
+
+    // A view's definition isn't extracted, so views are expected to exist in the target already.
+    for table in tables.iter().filter(|t| !t.is_view) {
+        writeln!(writer, "-- Schema for {}", comment_text(&table.table_name))?;
+        writeln!(writer, "CREATE TABLE {} (", quote_table(&table.schema_name, &table.table_name))?;
+
+        let mut lines: Vec<String> = table.columns.iter().map(column_definition).collect();
+        if !table.primary_keys.is_empty() {
+            let pk_cols: Vec<String> = table.primary_keys.iter().map(|c| quote_ident(c)).collect();
+            lines.push(format!("PRIMARY KEY ({})", pk_cols.join(", ")));
+        }
+        // Foreign keys may reference these instead of the primary key.
+        for key in &table.unique_keys {
+            let key_cols: Vec<String> = key.iter().map(|c| quote_ident(c)).collect();
+            lines.push(format!("UNIQUE ({})", key_cols.join(", ")));
+        }
+        // Only the (column, operator) pairs are extracted; gist covers the range overlaps these guard.
+        for constraint in &table.exclusion_constraints {
+            let elements: Vec<String> = constraint.iter()
+                .map(|(col, op)| format!("{} WITH {}", quote_ident(col), op))
+                .collect();
+            lines.push(format!("EXCLUDE USING gist ({})", elements.join(", ")));
+        }
+        writeln!(writer, "    {}", lines.join(",\n    "))?;
+        writeln!(writer, ");\n")?;
+    }
+
+    for table in tables {
+        // Inferred FKs only guide generation; the source has no such constraint.
+        for fk in table.foreign_keys.iter().filter(|fk| !fk.inferred) {
+            writeln!(
+                writer,
+                "ALTER TABLE {} ADD FOREIGN KEY ({}) REFERENCES {} ({}){}{}{};",
+                quote_table(&table.schema_name, &table.table_name),
+                quote_ident(&fk.column),
+                quote_table(&fk.ref_schema, &fk.ref_table),
+                quote_ident(&fk.ref_column),
+                referential_action("DELETE", fk.on_delete.as_deref()),
+                referential_action("UPDATE", fk.on_update.as_deref()),
+                if fk.deferrable { " DEFERRABLE" } else { "" },
+            )?;
+        }
+    }
+    writeln!(writer)?;
+
+    Ok(())
+}
+
+/// ` ON DELETE CASCADE` and the like; NO ACTION is the default and left out.
+fn referential_action(event: &str, action: Option<&str>) -> String {
+    match action {
+        Some(action) if action != "NO ACTION" => format!(" ON {} {}", event, action),
+        _ => String::new(),
+    }
+}
+
+fn column_definition(col: &Column) -> String {
+    let sql_type = if col.sql_type.is_empty() { col.data_type.as_str() } else { col.sql_type.as_str() };
+    let default = col.column_default.as_deref();
+
+    if let Some(expr) = &col.generation_expression {
+        return format!("{} {} GENERATED ALWAYS AS ({}) STORED", quote_ident(&col.name), sql_type, expr);
+    }
+    if let Some(kind) = &col.identity_generation {
+        return format!("{} {} GENERATED {} AS IDENTITY", quote_ident(&col.name), sql_type, kind);
+    }
+
+    // Sequence-backed defaults reference sequences we don't recreate; serial types bring their own.
+    let mut def = match (sql_type, default) {
+        ("integer", Some(d)) if d.starts_with("nextval(") => format!("{} serial", quote_ident(&col.name)),
+        ("bigint", Some(d)) if d.starts_with("nextval(") => format!("{} bigserial", quote_ident(&col.name)),
+        ("smallint", Some(d)) if d.starts_with("nextval(") => format!("{} smallserial", quote_ident(&col.name)),
+        (_, Some(d)) if !d.starts_with("nextval(") => format!("{} {} DEFAULT {}", quote_ident(&col.name), sql_type, d),
+        _ => format!("{} {}", quote_ident(&col.name), sql_type),
+    };
+    if !col.is_nullable {
+        def.push_str(" NOT NULL");
+    }
+    def
+}
@@ -1,24 +1,39 @@
+use crate::{ddl, ui};
 use crate::schema::{Table, Column};
-use rand::Rng;
+use crate::sql::{bytea_literal, comment_text, csv_field, escape_sql_string, quote_ident, quote_table, sql_literal_to_raw};
+use serde::{Deserialize, Serialize};
+use rand::{Rng, RngCore, SeedableRng};
+use rand::rngs::StdRng;
+use regex::Regex;
 use rand::seq::SliceRandom;
-use anyhow::Result;
-use std::fs::File;
-use std::io::{Write, BufWriter};
-use std::collections::{HashMap, HashSet, VecDeque};
+use rand_distr::{Distribution, Normal, Triangular, Zipf};
+use anyhow::{bail, Context, Result};
+use std::fs::{self, File};
+use std::io::{self, Write, BufReader, BufWriter};
+use std::path::Path;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::ops::ControlFlow;
 
 use fake::Fake;
 use fake::faker::name::en::*;
 use fake::faker::address::en::*;
 use fake::faker::company::en::*;
-use chrono::{Utc, Duration, NaiveDate};
+use fake::faker::lorem::en::Words;
+use chrono::{DateTime, Datelike, DurationRound, Utc, Duration, NaiveDate, NaiveDateTime};
+use deunicode::deunicode;
 use uuid::Uuid;
+use indicatif::ProgressBar;
 
 // ====================================================================================
 // DEEP LEARNING SEMANTIC ENGINE
 // ====================================================================================
 
-#[derive(Debug, Clone, PartialEq)]
-enum SemanticType {
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[allow(dead_code, clippy::upper_case_acronyms)]
+pub enum SemanticType {
     // Identity
     PrimaryKey,
     ForeignKey(String),
@@ -41,12 +56,15 @@ enum SemanticType {
     
     // Location (Geographic)
     Country,
+    CountryCode,
     State,
     City,
     StreetAddress,
     PostalCode,
     Latitude,
     Longitude,
+    GeoPoint,
+    CoordinatePair,
     GalacticCoordinate,
     
     // Contact
@@ -56,9 +74,15 @@ enum SemanticType {
     // Web/Network
     DomainName,
     URL,
+    AvatarURL,
+    ImageURL,
+    ProfileURL,
+    WebhookURL,
     EmailAddress,
     IPv4Address,
+    PublicIPv4Address,
     IPv6Address,
+    NetworkCidr,
     MacAddress,
     NetworkPort,
     
@@ -72,6 +96,9 @@ enum SemanticType {
     DateSigned,
     DateEstablished,
     Timestamp,
+    Date,
+    TimeOfDay,
+    Interval,
     
     // Financial
     MoneyAmount,
@@ -79,6 +106,8 @@ enum SemanticType {
     CreditValue,
     PriceValue,
     BalanceValue,
+    CreditCardNumber,
+    IBAN,
     
     // Cryptographic
     WalletAddress,
@@ -96,6 +125,7 @@ enum SemanticType {
     ClearanceLevel,
     RankTitle,
     PriorityLevel,
+    EnumLabel,
     
     // Identifiers/Codes
     TrackingCode,
@@ -137,6 +167,7 @@ enum SemanticType {
     DurationSeconds,
     DurationHours,
     ByteSize,
+    Percentage,
     
     // Technical
     FirmwareVersion,
@@ -148,6 +179,10 @@ enum SemanticType {
     DecimalValue,
     TextValue,
     JSONValue,
+    XMLValue,
+    HstoreValue,
+    RangeValue,
+    BinaryData,
 }
 
 struct DeepAnalyzer;
@@ -156,8 +191,9 @@ impl DeepAnalyzer {
     fn analyze_field_intelligence(
         field_name: &str,
         data_type: &str,
-        is_foreign_key: bool,
         foreign_table: Option<&str>,
+        declared_primary_key: Option<bool>,
+        comment: Option<&str>,
         sample_values: &[String],
         table_name: &str,
     ) -> SemanticType {
@@ -166,15 +202,34 @@ impl DeepAnalyzer {
         let table_lower = table_name.to_lowercase();
         
         // === FOREIGN KEY DETECTION ===
-        if is_foreign_key {
-            return SemanticType::ForeignKey(foreign_table.unwrap_or("unknown").to_string());
+        if let Some(foreign_table) = foreign_table {
+            return SemanticType::ForeignKey(foreign_table.to_string());
         }
         
         // === PRIMARY KEY DETECTION ===
-        if Self::is_primary_key(&field_lower, &table_lower) {
+        // Constraint metadata is authoritative; the name heuristic only fills in when it's missing.
+        let is_primary_key = declared_primary_key
+            .unwrap_or_else(|| Self::is_primary_key(&field_lower, &table_lower));
+        if is_primary_key {
             return SemanticType::PrimaryKey;
         }
         
+        // === COLUMN COMMENT HINTS ===
+        // A comment such as 'customer email address' is read like a column name and outranks
+        // the samples and the real name, unless all it yields is the plain type fallback.
+        if let Some(comment) = comment {
+            let hint: String = comment.to_lowercase()
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect();
+            let hinted = Self::deep_semantic_inference(&hint, &type_lower, &table_lower);
+            let fallback = matches!(hinted,
+                SemanticType::IntegerValue | SemanticType::DecimalValue | SemanticType::TextValue | SemanticType::JSONValue);
+            if !fallback && (type_lower != "date" || Self::is_date_type(&hinted)) {
+                return hinted;
+            }
+        }
+        
         // === LEARN FROM SAMPLE DATA (if available) ===
         if !sample_values.is_empty() {
             if let Some(inferred_type) = Self::infer_from_samples(&field_lower, sample_values) {
@@ -189,9 +244,51 @@ impl DeepAnalyzer {
         if type_lower.contains("bool") {
             return SemanticType::BooleanValue;
         }
+        if type_lower == "interval" {
+            return SemanticType::Interval;
+        }
+        if type_lower.starts_with("time ") {
+            return SemanticType::TimeOfDay;
+        }
+        if type_lower == "bytea" {
+            return SemanticType::BinaryData;
+        }
+        if type_lower == "cidr" {
+            return SemanticType::NetworkCidr;
+        }
+        if type_lower == "point" {
+            return SemanticType::GeoPoint;
+        }
+        if type_lower == "xml" {
+            return SemanticType::XMLValue;
+        }
+        if type_lower == "hstore" {
+            return SemanticType::HstoreValue;
+        }
+        if RangeKind::of(&type_lower).is_some() {
+            return SemanticType::RangeValue;
+        }
         
         // === DEEP SEMANTIC ANALYSIS ===
-        Self::deep_semantic_inference(&field_lower, &type_lower, &table_lower)
+        let inferred = Self::deep_semantic_inference(&field_lower, &type_lower, &table_lower);
+        // A date column only takes the name-based guess when that guess produces a date.
+        if type_lower == "date" && !Self::is_date_type(&inferred) {
+            return SemanticType::Date;
+        }
+        // Likewise an inet column only holds addresses.
+        if type_lower == "inet" && !matches!(inferred,
+            SemanticType::IPv4Address | SemanticType::PublicIPv4Address | SemanticType::IPv6Address | SemanticType::NetworkCidr) {
+            return SemanticType::IPv4Address;
+        }
+        inferred
+    }
+    
+    fn is_date_type(semantic: &SemanticType) -> bool {
+        matches!(semantic,
+            SemanticType::BirthDate | SemanticType::DateStart | SemanticType::DateEnd | SemanticType::DateCreated |
+            SemanticType::DateUpdated | SemanticType::DateRegistered | SemanticType::DateLaunched |
+            SemanticType::DateSigned | SemanticType::DateEstablished | SemanticType::Timestamp | SemanticType::Date
+        )
     }
     
     fn is_primary_key(field: &str, _table: &str) -> bool {
@@ -199,7 +296,12 @@ impl DeepAnalyzer {
         field.ends_with("_id") && !field.contains("user") && !field.contains("account") && !field.contains("badge") && !field.contains("ref")
     }
     
-    fn infer_from_samples(field: &str, samples: &[String]) -> Option<SemanticType> {
+    fn infer_from_samples(_field: &str, samples: &[String]) -> Option<SemanticType> {
+        // Flags stored as 0/1, Y/N, t/f, ... (needs the whole sampled set, not one value)
+        if Self::boolean_pair(samples).is_some() {
+            return Some(SemanticType::BooleanValue);
+        }
+        
         let sample = samples[0].to_lowercase();
         
         // MAC Address pattern (xx:xx:xx:xx:xx:xx)
@@ -235,6 +337,25 @@ impl DeepAnalyzer {
         None
     }
     
+    /// When the sampled values are exactly a true/false pair in some spelling, returns them
+    /// as `(true_value, false_value)` in the source's own form.
+    fn boolean_pair(samples: &[String]) -> Option<(String, String)> {
+        const PAIRS: [(&str, &str); 6] = [("1", "0"), ("y", "n"), ("yes", "no"), ("t", "f"), ("true", "false"), ("on", "off")];
+        if samples.len() != 2 {
+            return None;
+        }
+        let (a, b) = (samples[0].trim().to_lowercase(), samples[1].trim().to_lowercase());
+        PAIRS.iter().find_map(|&(t, f)| {
+            if a == t && b == f {
+                Some((samples[0].clone(), samples[1].clone()))
+            } else if a == f && b == t {
+                Some((samples[1].clone(), samples[0].clone()))
+            } else {
+                None
+            }
+        })
+    }
+    
     fn deep_semantic_inference(field: &str, dtype: &str, _table: &str) -> SemanticType {
         // === NAMES & IDENTITY ===
         if field.contains("first") && field.contains("name") { return SemanticType::FirstName; }
@@ -271,6 +392,9 @@ impl DeepAnalyzer {
         }
         
         // === LOCATION (EARTH) ===
+        if field.contains("country") && (field.contains("code") || field.contains("iso")) {
+            return SemanticType::CountryCode;
+        }
         if field.contains("country") { return SemanticType::Country; }
         if field.contains("state") && !field.contains("status") { return SemanticType::State; }
         if field.contains("city") { return SemanticType::City; }
@@ -303,8 +427,30 @@ impl DeepAnalyzer {
             return SemanticType::DomainName;
         }
         if field.contains("url") || field.contains("website") {
+            // What the link points at decides its path shape.
+            if field.contains("avatar") {
+                return SemanticType::AvatarURL;
+            }
+            if ["image", "img", "photo", "picture", "thumbnail", "logo", "banner"].iter().any(|k| field.contains(k)) {
+                return SemanticType::ImageURL;
+            }
+            if field.contains("profile") {
+                return SemanticType::ProfileURL;
+            }
+            if field.contains("webhook") || field.contains("callback") {
+                return SemanticType::WebhookURL;
+            }
             return SemanticType::URL;
         }
+        if field.contains("cidr") || field.contains("subnet") {
+            return SemanticType::NetworkCidr;
+        }
+        let internal = INTERNAL_IP_HINTS.iter().any(|k| field.contains(k));
+        let client_facing = ["client_ip", "remote_addr", "remote_ip", "public_ip", "source_ip", "src_ip",
+            "user_ip", "visitor_ip", "origin_ip", "login_ip", "request_ip"];
+        if !internal && client_facing.iter().any(|k| field.contains(k)) {
+            return SemanticType::PublicIPv4Address;
+        }
         if dtype.contains("inet") || field.contains("ipv4") || (field.contains("ip") && field.contains("address") && !field.contains("ipv6")) {
             return SemanticType::IPv4Address;
         }
@@ -318,7 +464,25 @@ impl DeepAnalyzer {
             return SemanticType::NetworkPort;
         }
         
+        // === RATIOS ===
+        // Matched on whole name parts so `generated`/`migrated` don't read as a rate.
+        let numeric = ["int", "numeric", "decimal", "real", "double"].iter().any(|t| dtype.contains(t));
+        let parts: Vec<&str> = field.split('_').collect();
+        let ratio_part = parts.iter().any(|p| ["percent", "percentage", "pct", "rate", "ratio"].contains(p));
+        let not_a_share = parts.iter().any(|p| NON_RATIO_RATES.contains(p));
+        if numeric && ratio_part && !not_a_share {
+            return SemanticType::Percentage;
+        }
+        
         // === FINANCIAL ===
+        let number_part = field.contains("number") || field.ends_with("_no") || field.ends_with("_num");
+        if (field.contains("card") && number_part) || field == "pan" || field == "card_pan" {
+            return SemanticType::CreditCardNumber;
+        }
+        let text = dtype == "text" || dtype.contains("char");
+        if field.contains("iban") || (text && field.contains("account") && number_part) {
+            return SemanticType::IBAN;
+        }
         if field.contains("price") || field.contains("cost") {
             return SemanticType::PriceValue;
         }
@@ -490,14 +654,23 @@ impl DeepAnalyzer {
     fn get_generation_priority(semantic_type: &SemanticType) -> u8 {
         match semantic_type {
             SemanticType::PrimaryKey => 100,
-            SemanticType::FirstName | SemanticType::LastName | SemanticType::FullName => 95,
-            SemanticType::Gender => 94,
+            SemanticType::ForeignKey(_) => 96,
+            // Gender first so first names can match it.
+            SemanticType::Gender => 95,
+            SemanticType::FirstName | SemanticType::LastName | SemanticType::FullName => 94,
             SemanticType::CompanyName | SemanticType::OrganizationName => 93,
-            SemanticType::DateSigned | SemanticType::DateEstablished | SemanticType::DateCreated | SemanticType::DateStart => 90,
+            SemanticType::DateSigned | SemanticType::DateEstablished | SemanticType::DateCreated | SemanticType::DateStart |
+            SemanticType::DateRegistered | SemanticType::DateLaunched => 90,
             SemanticType::DateEnd => 85,
             SemanticType::Username => 80,
             SemanticType::DomainName => 78,
             SemanticType::EmailAddress => 75,
+            // Places come before coordinates so the lat/long pair can be biased toward them.
+            SemanticType::Country | SemanticType::CountryCode | SemanticType::State | SemanticType::City => 60,
+            // Currency after the country it's spent in, before amounts so they get its minor units.
+            SemanticType::CurrencyCode => 55,
+            // Birth date before age so the age can be derived from it.
+            SemanticType::BirthDate => 55,
             _ => 50,
         }
     }
@@ -507,15 +680,24 @@ impl DeepAnalyzer {
 // CONTEXT ENGINE
 // ====================================================================================
 
+/// What has been generated so far for the row being built; custom providers read it to stay
+/// consistent with the other columns.
 #[derive(Debug, Clone, Default)]
-struct ContextEngine {
+pub struct ContextEngine {
+    table: String,
+    row_pk: Option<String>,
     data: HashMap<String, String>,
-    dates: HashMap<String, NaiveDate>,
+    dates: HashMap<String, NaiveDateTime>,
+    parent_location: Option<Location>,
+    coordinates: Option<(f64, f64)>,
+    place: Option<&'static GeoAnchor>, // City/state/country triple every place column of the row draws from
+    gender: Option<NameGender>,
+    birth_date: Option<NaiveDate>,
 }
 
 impl ContextEngine {
-    fn new() -> Self {
-        Self::default()
+    fn new(table: &str) -> Self {
+        Self { table: table.to_string(), ..Self::default() }
     }
     
     fn set(&mut self, key: &str, value: &str) {
@@ -524,21 +706,772 @@ impl ContextEngine {
         }
     }
     
-    fn get(&self, key: &str) -> Option<&String> {
+    /// The raw (unquoted) value of an earlier column of this row, by column name or by a
+    /// derived key such as `first_name`, `city` or `currency`.
+    pub fn get(&self, key: &str) -> Option<&String> {
         self.data.get(&key.to_lowercase())
     }
     
-    fn set_date(&mut self, key: &str, date: NaiveDate) {
+    fn set_date(&mut self, key: &str, date: NaiveDateTime) {
         self.dates.insert(key.to_lowercase(), date);
     }
     
-    fn get_any_start_date(&self) -> Option<NaiveDate> {
-        for (key, date) in &self.dates {
-            if key.contains("signed") || key.contains("created") || key.contains("established") || key.contains("start") || key.contains("launch") {
-                return Some(*date);
+    fn inherited_location(&self, semantic: &SemanticType) -> Option<&String> {
+        self.parent_location.as_ref().and_then(|loc| loc.get(semantic))
+    }
+    
+    /// The parent row's latitude/longitude pair, when it had both.
+    fn inherited_coordinates(&self) -> Option<(f64, f64)> {
+        let lat = self.inherited_location(&SemanticType::Latitude)?.parse().ok()?;
+        let lon = self.inherited_location(&SemanticType::Longitude)?.parse().ok()?;
+        Some((lat, lon))
+    }
+    
+    /// The latest lifecycle date already generated for this row; updates can't precede it.
+    fn latest_date(&self) -> Option<NaiveDateTime> {
+        self.dates.values().max().copied()
+    }
+    
+    /// The latest start-like date of the row; taking the latest rather than whichever the map
+    /// yields first keeps seeded runs repeatable.
+    fn get_any_start_date(&self) -> Option<NaiveDateTime> {
+        self.dates.iter()
+            .filter(|(key, _)| ["signed", "created", "established", "start", "launch"].iter().any(|k| key.contains(k)))
+            .map(|(_, date)| *date)
+            .max()
+    }
+}
+
+/// Geographic fields of a generated row, cached per PK so FK children can inherit them.
+#[derive(Debug, Clone, Default)]
+struct Location {
+    country: Option<String>,
+    state: Option<String>,
+    city: Option<String>,
+    latitude: Option<String>,
+    longitude: Option<String>,
+}
+
+impl Location {
+    fn slot_mut(&mut self, semantic: &SemanticType) -> Option<&mut Option<String>> {
+        match semantic {
+            SemanticType::Country => Some(&mut self.country),
+            SemanticType::State => Some(&mut self.state),
+            SemanticType::City => Some(&mut self.city),
+            SemanticType::Latitude => Some(&mut self.latitude),
+            SemanticType::Longitude => Some(&mut self.longitude),
+            _ => None,
+        }
+    }
+    
+    fn get(&self, semantic: &SemanticType) -> Option<&String> {
+        match semantic {
+            SemanticType::Country => self.country.as_ref(),
+            SemanticType::State => self.state.as_ref(),
+            SemanticType::City => self.city.as_ref(),
+            SemanticType::Latitude => self.latitude.as_ref(),
+            SemanticType::Longitude => self.longitude.as_ref(),
+            _ => None,
+        }
+    }
+    
+    fn is_empty(&self) -> bool {
+        self.country.is_none() && self.state.is_none() && self.city.is_none()
+            && self.latitude.is_none() && self.longitude.is_none()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NameGender {
+    Male,
+    Female,
+}
+
+impl NameGender {
+    /// Reads "male", "F", "Woman", ... ; anything else (other, unknown, n/a) has no gendered names.
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().chars().next() {
+            Some('m') => Some(Self::Male),
+            Some('f') | Some('w') => Some(Self::Female),
+            _ => None,
+        }
+    }
+
+    fn first_name(self, rng: &mut impl Rng) -> &'static str {
+        let names = match self {
+            Self::Male => MALE_FIRST_NAMES,
+            Self::Female => FEMALE_FIRST_NAMES,
+        };
+        names.choose(rng).unwrap()
+    }
+}
+
+const MALE_FIRST_NAMES: &[&str] = &[
+    "James", "Robert", "John", "Michael", "David", "William", "Richard", "Joseph", "Thomas", "Charles",
+    "Christopher", "Daniel", "Matthew", "Anthony", "Mark", "Donald", "Steven", "Paul", "Andrew", "Joshua",
+    "Kenneth", "Kevin", "Brian", "George", "Timothy", "Ronald", "Jason", "Edward", "Jeffrey", "Ryan",
+    "Jacob", "Gary", "Nicholas", "Eric", "Jonathan", "Stephen", "Larry", "Justin", "Scott", "Benjamin",
+];
+
+const FEMALE_FIRST_NAMES: &[&str] = &[
+    "Mary", "Patricia", "Jennifer", "Linda", "Elizabeth", "Barbara", "Susan", "Jessica", "Sarah", "Karen",
+    "Lisa", "Nancy", "Betty", "Sandra", "Margaret", "Ashley", "Kimberly", "Emily", "Donna", "Michelle",
+    "Carol", "Amanda", "Melissa", "Deborah", "Stephanie", "Rebecca", "Sharon", "Laura", "Cynthia", "Amy",
+    "Kathleen", "Angela", "Shirley", "Brenda", "Emma", "Anna", "Pamela", "Nicole", "Samantha", "Olivia",
+];
+
+/// Land reference point as (city, state/region, country, latitude, longitude). Country names
+/// match the faker's spelling so generated countries bias coordinates toward the right region;
+/// cities and regions keep their local accents (`--ascii-only` folds them).
+type GeoAnchor = (&'static str, &'static str, &'static str, f64, f64);
+
+const GEO_ANCHORS: &[GeoAnchor] = &[
+    ("New York", "New York", "United States of America", 40.7128, -74.0060),
+    ("Buffalo", "New York", "United States of America", 42.8864, -78.8784),
+    ("Los Angeles", "California", "United States of America", 34.0522, -118.2437),
+    ("San Francisco", "California", "United States of America", 37.7749, -122.4194),
+    ("San Diego", "California", "United States of America", 32.7157, -117.1611),
+    ("Chicago", "Illinois", "United States of America", 41.8781, -87.6298),
+    ("Houston", "Texas", "United States of America", 29.7604, -95.3698),
+    ("Austin", "Texas", "United States of America", 30.2672, -97.7431),
+    ("Dallas", "Texas", "United States of America", 32.7767, -96.7970),
+    ("Seattle", "Washington", "United States of America", 47.6062, -122.3321),
+    ("Boston", "Massachusetts", "United States of America", 42.3601, -71.0589),
+    ("Miami", "Florida", "United States of America", 25.7617, -80.1918),
+    ("Orlando", "Florida", "United States of America", 28.5384, -81.3789),
+    ("Denver", "Colorado", "United States of America", 39.7392, -104.9903),
+    ("Atlanta", "Georgia", "United States of America", 33.7490, -84.3880),
+    ("Phoenix", "Arizona", "United States of America", 33.4484, -112.0740),
+    ("Toronto", "Ontario", "Canada", 43.6532, -79.3832),
+    ("Ottawa", "Ontario", "Canada", 45.4215, -75.6972),
+    ("Montréal", "Québec", "Canada", 45.5019, -73.5674),
+    ("Vancouver", "British Columbia", "Canada", 49.2827, -123.1207),
+    ("Calgary", "Alberta", "Canada", 51.0447, -114.0719),
+    ("Mexico City", "Mexico City", "Mexico", 19.4326, -99.1332),
+    ("Guadalajara", "Jalisco", "Mexico", 20.6597, -103.3496),
+    ("São Paulo", "São Paulo", "Brazil", -23.5505, -46.6333),
+    ("Rio de Janeiro", "Rio de Janeiro", "Brazil", -22.9068, -43.1729),
+    ("Buenos Aires", "Buenos Aires", "Argentina", -34.6037, -58.3816),
+    ("Lima", "Lima", "Peru", -12.0464, -77.0428),
+    ("Bogotá", "Cundinamarca", "Colombia", 4.7110, -74.0721),
+    ("London", "England", "United Kingdom", 51.5074, -0.1278),
+    ("Manchester", "England", "United Kingdom", 53.4808, -2.2426),
+    ("Edinburgh", "Scotland", "United Kingdom", 55.9533, -3.1883),
+    ("Glasgow", "Scotland", "United Kingdom", 55.8642, -4.2518),
+    ("Dublin", "Leinster", "Ireland", 53.3498, -6.2603),
+    ("Paris", "Île-de-France", "France", 48.8566, 2.3522),
+    ("Lyon", "Auvergne-Rhône-Alpes", "France", 45.7640, 4.8357),
+    ("Marseille", "Provence-Alpes-Côte d'Azur", "France", 43.2965, 5.3698),
+    ("Berlin", "Berlin", "Germany", 52.5200, 13.4050),
+    ("Munich", "Bavaria", "Germany", 48.1351, 11.5820),
+    ("Nuremberg", "Bavaria", "Germany", 49.4521, 11.0767),
+    ("Hamburg", "Hamburg", "Germany", 53.5511, 9.9937),
+    ("Zürich", "Zürich", "Switzerland", 47.3769, 8.5417),
+    ("Madrid", "Community of Madrid", "Spain", 40.4168, -3.7038),
+    ("Barcelona", "Catalonia", "Spain", 41.3874, 2.1686),
+    ("Rome", "Lazio", "Italy", 41.9028, 12.4964),
+    ("Milan", "Lombardy", "Italy", 45.4642, 9.1900),
+    ("Amsterdam", "North Holland", "Netherlands", 52.3676, 4.9041),
+    ("Stockholm", "Stockholm County", "Sweden", 59.3293, 18.0686),
+    ("Malmö", "Skåne", "Sweden", 55.6050, 13.0038),
+    ("Warsaw", "Masovia", "Poland", 52.2297, 21.0122),
+    ("Kraków", "Lesser Poland", "Poland", 50.0647, 19.9450),
+    ("Moscow", "Moscow", "Russian Federation", 55.7558, 37.6173),
+    ("Istanbul", "Istanbul", "Turkey", 41.0082, 28.9784),
+    ("Cairo", "Cairo", "Egypt", 30.0444, 31.2357),
+    ("Lagos", "Lagos", "Nigeria", 6.5244, 3.3792),
+    ("Nairobi", "Nairobi", "Kenya", -1.2921, 36.8219),
+    ("Johannesburg", "Gauteng", "South Africa", -26.2041, 28.0473),
+    ("Cape Town", "Western Cape", "South Africa", -33.9249, 18.4241),
+    ("Dubai", "Dubai", "United Arab Emirates", 25.2048, 55.2708),
+    ("Mumbai", "Maharashtra", "India", 19.0760, 72.8777),
+    ("Pune", "Maharashtra", "India", 18.5204, 73.8567),
+    ("Delhi", "Delhi", "India", 28.7041, 77.1025),
+    ("Bangalore", "Karnataka", "India", 12.9716, 77.5946),
+    ("Bangkok", "Bangkok", "Thailand", 13.7563, 100.5018),
+    ("Singapore", "Singapore", "Singapore", 1.3521, 103.8198),
+    ("Jakarta", "Jakarta", "Indonesia", -6.2088, 106.8456),
+    ("Beijing", "Beijing", "China", 39.9042, 116.4074),
+    ("Shanghai", "Shanghai", "China", 31.2304, 121.4737),
+    ("Guangzhou", "Guangdong", "China", 23.1291, 113.2644),
+    ("Shenzhen", "Guangdong", "China", 22.5431, 114.0579),
+    ("Seoul", "Seoul", "Republic of Korea", 37.5665, 126.9780),
+    ("Tokyo", "Tokyo", "Japan", 35.6762, 139.6503),
+    ("Osaka", "Osaka", "Japan", 34.6937, 135.5023),
+    ("Budapest", "Budapest", "Hungary", 47.4979, 19.0402),
+    ("Debrecen", "Hajdú-Bihar", "Hungary", 47.5316, 21.6273),
+    ("Sydney", "New South Wales", "Australia", -33.8688, 151.2093),
+    ("Melbourne", "Victoria", "Australia", -37.8136, 144.9631),
+    ("Brisbane", "Queensland", "Australia", -27.4698, 153.0251),
+    ("Perth", "Western Australia", "Australia", -31.9505, 115.8605),
+    ("Auckland", "Auckland", "New Zealand", -36.8485, 174.7633),
+];
+
+/// Anchors agreeing with the city/state/country the row already has, the most specific known
+/// field winning. Every anchor when none is known; `None` when the known values match no anchor.
+fn matching_anchors(ctx: &ContextEngine) -> Option<Vec<&'static GeoAnchor>> {
+    let mut any_known = false;
+    for (level, key) in ["city", "state", "country"].into_iter().enumerate() {
+        if let Some(value) = ctx.get(key) {
+            any_known = true;
+            let found: Vec<&GeoAnchor> = GEO_ANCHORS.iter()
+                .filter(|a| same_place([a.0, a.1, a.2][level], value))
+                .collect();
+            if !found.is_empty() {
+                return Some(found);
             }
         }
-        None
+    }
+    if any_known { None } else { Some(GEO_ANCHORS.iter().collect()) }
+}
+
+/// Place names compared without case or accents, so `Sao Paulo` in the source finds `São Paulo`.
+fn same_place(anchor: &str, value: &str) -> bool {
+    deunicode(anchor).eq_ignore_ascii_case(&deunicode(value.trim()))
+}
+
+/// Picks a plausible (latitude, longitude) pair on land. A known city in the row pins the pair
+/// close to it, a known state or country keeps it inside that region's anchors, otherwise any anchor.
+fn plausible_coordinates(ctx: &ContextEngine, rng: &mut impl Rng) -> (f64, f64) {
+    let candidates = matching_anchors(ctx).unwrap_or_else(|| GEO_ANCHORS.iter().collect());
+    let near_city = ctx.get("city").is_some_and(|city| candidates.iter().any(|a| same_place(a.0, city)));
+    let spread = if near_city { 0.05 } else { 0.5 };
+
+    let (_, _, _, lat, lon) = candidates.choose(rng).unwrap();
+    let latitude = (lat + rng.gen_range(-spread..spread)).clamp(-90.0, 90.0);
+    let longitude = (lon + rng.gen_range(-spread..spread)).clamp(-180.0, 180.0);
+    (latitude, longitude)
+}
+
+/// Country as (name, ISO 3166 alpha-2, alpha-3, ISO 4217 currency). Names use the faker's
+/// spelling, and every GEO_ANCHORS country is listed so a row's place always has its codes.
+type CountryCodes = (&'static str, &'static str, &'static str, &'static str);
+
+const COUNTRY_CODES: &[CountryCodes] = &[
+    ("United States of America", "US", "USA", "USD"),
+    ("Canada", "CA", "CAN", "CAD"),
+    ("Mexico", "MX", "MEX", "MXN"),
+    ("Brazil", "BR", "BRA", "BRL"),
+    ("Argentina", "AR", "ARG", "ARS"),
+    ("Peru", "PE", "PER", "PEN"),
+    ("Colombia", "CO", "COL", "COP"),
+    ("Chile", "CL", "CHL", "CLP"),
+    ("United Kingdom", "GB", "GBR", "GBP"),
+    ("Ireland", "IE", "IRL", "EUR"),
+    ("France", "FR", "FRA", "EUR"),
+    ("Germany", "DE", "DEU", "EUR"),
+    ("Switzerland", "CH", "CHE", "CHF"),
+    ("Spain", "ES", "ESP", "EUR"),
+    ("Portugal", "PT", "PRT", "EUR"),
+    ("Italy", "IT", "ITA", "EUR"),
+    ("Netherlands", "NL", "NLD", "EUR"),
+    ("Belgium", "BE", "BEL", "EUR"),
+    ("Austria", "AT", "AUT", "EUR"),
+    ("Sweden", "SE", "SWE", "SEK"),
+    ("Norway", "NO", "NOR", "NOK"),
+    ("Denmark", "DK", "DNK", "DKK"),
+    ("Finland", "FI", "FIN", "EUR"),
+    ("Poland", "PL", "POL", "PLN"),
+    ("Czech Republic", "CZ", "CZE", "CZK"),
+    ("Hungary", "HU", "HUN", "HUF"),
+    ("Greece", "GR", "GRC", "EUR"),
+    ("Russian Federation", "RU", "RUS", "RUB"),
+    ("Turkey", "TR", "TUR", "TRY"),
+    ("Egypt", "EG", "EGY", "EGP"),
+    ("Nigeria", "NG", "NGA", "NGN"),
+    ("Kenya", "KE", "KEN", "KES"),
+    ("South Africa", "ZA", "ZAF", "ZAR"),
+    ("Morocco", "MA", "MAR", "MAD"),
+    ("United Arab Emirates", "AE", "ARE", "AED"),
+    ("Saudi Arabia", "SA", "SAU", "SAR"),
+    ("Kuwait", "KW", "KWT", "KWD"),
+    ("Bahrain", "BH", "BHR", "BHD"),
+    ("Israel", "IL", "ISR", "ILS"),
+    ("India", "IN", "IND", "INR"),
+    ("Pakistan", "PK", "PAK", "PKR"),
+    ("Thailand", "TH", "THA", "THB"),
+    ("Singapore", "SG", "SGP", "SGD"),
+    ("Malaysia", "MY", "MYS", "MYR"),
+    ("Indonesia", "ID", "IDN", "IDR"),
+    ("Philippines", "PH", "PHL", "PHP"),
+    ("Vietnam", "VN", "VNM", "VND"),
+    ("China", "CN", "CHN", "CNY"),
+    ("Hong Kong", "HK", "HKG", "HKD"),
+    ("Taiwan", "TW", "TWN", "TWD"),
+    ("Republic of Korea", "KR", "KOR", "KRW"),
+    ("Japan", "JP", "JPN", "JPY"),
+    ("Australia", "AU", "AUS", "AUD"),
+    ("New Zealand", "NZ", "NZL", "NZD"),
+];
+
+/// Looks a country up by name or by either ISO code.
+fn iso_country(value: &str) -> Option<&'static CountryCodes> {
+    let value = value.trim();
+    COUNTRY_CODES.iter().find(|c| {
+        same_place(c.0, value) || c.1.eq_ignore_ascii_case(value) || c.2.eq_ignore_ascii_case(value)
+    })
+}
+
+/// `YYYY-MM-DD HH:MM:SS` with as many fractional digits as the column's declared precision
+/// (`timestamp(3)` -> milliseconds), truncated rather than rounded; none when unknown.
+fn timestamp_text(ts: DateTime<Utc>, precision: Option<i32>) -> String {
+    let seconds = ts.format("%Y-%m-%d %H:%M:%S").to_string();
+    match precision.unwrap_or(0).clamp(0, 6) as usize {
+        0 => seconds,
+        digits => format!("{}.{}", seconds, &format!("{:06}", ts.timestamp_subsec_micros())[..digits]),
+    }
+}
+
+/// A date-like value for `col`: a timestamp at the column's precision for timestamp types,
+/// the bare day otherwise.
+fn date_text(ts: DateTime<Utc>, col: &Column) -> String {
+    if col.data_type.contains("timestamp") {
+        timestamp_text(ts, col.datetime_precision)
+    } else {
+        ts.format("%Y-%m-%d").to_string()
+    }
+}
+
+/// Built-in range types, by the unit their bounds are counted in.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RangeKind {
+    Integer,
+    Numeric,
+    Date,
+    Timestamp,
+    TimestampTz,
+}
+
+impl RangeKind {
+    fn of(data_type: &str) -> Option<Self> {
+        match data_type {
+            "int4range" | "int8range" => Some(Self::Integer),
+            "numrange" => Some(Self::Numeric),
+            "daterange" => Some(Self::Date),
+            "tsrange" => Some(Self::Timestamp),
+            "tstzrange" => Some(Self::TimestampTz),
+            _ => None,
+        }
+    }
+
+    /// A random `(gap, length)` in the kind's unit: plain numbers, days, or quarter hours for
+    /// timestamps (an hour up to a working day).
+    fn span(self, rng: &mut impl Rng) -> (i64, i64) {
+        match self {
+            Self::Integer | Self::Numeric => (rng.gen_range(0..20), rng.gen_range(1..100)),
+            Self::Date => (rng.gen_range(0..14), rng.gen_range(1..30)),
+            Self::Timestamp | Self::TimestampTz => (rng.gen_range(0..8), rng.gen_range(4..32)),
+        }
+    }
+
+    /// Where a range may start, in the kind's unit: low numbers, or the two years (dates) or
+    /// one year (timestamps) before the anchor.
+    fn window(self) -> std::ops::Range<i64> {
+        match self {
+            Self::Integer | Self::Numeric => 0..1000,
+            Self::Date => -730..0,
+            Self::Timestamp | Self::TimestampTz => -365 * 96..0,
+        }
+    }
+
+    /// The non-empty `[start,end)` literal for offsets counted from `anchor`.
+    fn literal(self, anchor: DateTime<Utc>, start: i64, end: i64) -> String {
+        let day = anchor.date_naive();
+        let hour = anchor.duration_trunc(Duration::hours(1)).unwrap_or(anchor);
+        let quarter = |n: i64| timestamp_text(hour + Duration::minutes(n * 15), None);
+        match self {
+            Self::Integer | Self::Numeric => format!("[{},{})", start, end),
+            Self::Date => format!("[{},{})", day + Duration::days(start), day + Duration::days(end)),
+            Self::Timestamp => format!("[\"{}\",\"{}\")", quarter(start), quarter(end)),
+            Self::TimestampTz => format!("[\"{}+00\",\"{}+00\")", quarter(start), quarter(end)),
+        }
+    }
+}
+
+/// Whole years from `birth` to `today`, one less while this year's birthday is still ahead.
+fn age_on(birth: NaiveDate, today: NaiveDate) -> i32 {
+    let years = today.year() - birth.year();
+    if (today.month(), today.day()) < (birth.month(), birth.day()) { years - 1 } else { years }
+}
+
+/// Decimal places an amount in this ISO 4217 (or common crypto) currency is written with:
+/// 0 for yen-style currencies, 3 for the Gulf dinars, up to 8 for bitcoin, otherwise 2.
+fn currency_minor_units(code: &str) -> usize {
+    match code.trim().to_uppercase().as_str() {
+        "JPY" | "KRW" | "VND" | "CLP" | "ISK" | "PYG" | "UGX" | "XAF" | "XOF" | "IDR" => 0,
+        "BHD" | "KWD" | "OMR" | "JOD" | "TND" | "IQD" | "LYD" => 3,
+        "USDT" | "USDC" => 6,
+        "BTC" | "ETH" | "LTC" => 8,
+        _ => 2,
+    }
+}
+
+/// Card prefixes the networks reserve for testing (Visa, Mastercard, Amex, Discover), with the
+/// card length: payment sandboxes accept them and no real card carries them.
+const TEST_CARD_PREFIXES: &[(&str, usize)] = &[
+    ("411111", 16), ("424242", 16), ("400000", 16), ("555555", 16), ("510510", 16), ("378282", 15), ("371449", 15), ("601111", 16),
+];
+
+/// A card number under a test prefix, ending in its Luhn check digit.
+fn test_card_number(rng: &mut impl Rng) -> String {
+    let (prefix, length) = TEST_CARD_PREFIXES.choose(rng).unwrap();
+    let mut digits: Vec<u32> = prefix.chars().filter_map(|c| c.to_digit(10)).collect();
+    while digits.len() < length - 1 {
+        digits.push(rng.gen_range(0..10));
+    }
+    digits.push(luhn_check_digit(&digits));
+    digits.iter().map(|d| char::from_digit(*d, 10).unwrap()).collect()
+}
+
+/// The digit that makes `payload` pass the Luhn check: every second digit from the right,
+/// starting next to the check digit, is doubled (digits of the product summed).
+fn luhn_check_digit(payload: &[u32]) -> u32 {
+    let sum: u32 = payload.iter().rev().enumerate()
+        .map(|(i, &d)| if i % 2 == 0 { (d * 2) / 10 + (d * 2) % 10 } else { d })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+/// BBAN layout of an IBAN country: `9` is a digit, `A` an uppercase letter.
+const IBAN_FORMATS: &[(&str, &str)] = &[
+    ("DE", "999999999999999999"),
+    ("GB", "AAAA99999999999999"),
+    ("FR", "99999999999999999999999"),
+    ("ES", "99999999999999999999"),
+    ("IT", "A9999999999999999999999"),
+    ("NL", "AAAA9999999999"),
+    ("BE", "999999999999"),
+    ("AT", "9999999999999999"),
+    ("CH", "99999999999999999"),
+];
+
+/// An IBAN with valid mod-97 check digits for the `country` (ISO alpha-2) when it has a known
+/// layout, else for a random one. Bank and account digits are random, so the check digits
+/// hold but no real account is behind them. Only layouts within `max_length` are used.
+fn iban(country: Option<&str>, max_length: Option<i32>, rng: &mut impl Rng) -> String {
+    let fitting: Vec<&(&str, &str)> = IBAN_FORMATS.iter()
+        .filter(|(_, bban)| max_length.is_none_or(|max| bban.len() + 4 <= max as usize))
+        .collect();
+    let format = country.and_then(|code| fitting.iter().find(|(c, _)| *c == code))
+        .or_else(|| fitting.choose(rng))
+        .copied()
+        .unwrap_or(&IBAN_FORMATS[6]);
+    let (country, layout) = *format;
+    let bban: String = layout.chars()
+        .map(|c| match c {
+            'A' => (b'A' + rng.gen_range(0..26)) as char,
+            _ => char::from_digit(rng.gen_range(0..10), 10).unwrap(),
+        })
+        .collect();
+    // ISO 13616: move the country and "00" behind the BBAN, read letters as 10..35, take mod 97.
+    let remainder = format!("{}{}00", bban, country).chars()
+        .fold(0u32, |acc, c| {
+            let value = c.to_digit(36).unwrap();
+            if value < 10 { (acc * 10 + value) % 97 } else { (acc * 100 + value) % 97 }
+        });
+    format!("{}{:02}{}", country, 98 - remainder, bban)
+}
+
+/// A PostGIS column that can hold a single point.
+struct PostgisPoint {
+    geography: bool,
+    srid: i32, // 0 when the column doesn't pin one
+}
+
+/// Recognizes `geometry`, `geometry(Point[,srid])` and their `geography` counterparts in a
+/// declared type (possibly schema-qualified); other shapes can't take a point.
+fn postgis_point(sql_type: &str) -> Option<PostgisPoint> {
+    let lower = sql_type.to_lowercase();
+    let (name, modifier) = match lower.split_once('(') {
+        Some((name, rest)) => (name, Some(rest.trim_end_matches(')'))),
+        None => (lower.as_str(), None),
+    };
+    let geography = match name.rsplit('.').next() {
+        Some("geometry") => false,
+        Some("geography") => true,
+        _ => return None,
+    };
+    let srid = match modifier.map(|m| m.split(',').map(str::trim).collect::<Vec<_>>()) {
+        None => if geography { 4326 } else { 0 },
+        Some(parts) if parts[0] == "point" => parts.get(1).and_then(|s| s.parse().ok()).unwrap_or(0),
+        Some(_) => return None,
+    };
+    Some(PostgisPoint { geography, srid })
+}
+
+/// Name parts that make a `*_rate` column a speed, price or exchange rate rather than a share.
+const NON_RATIO_RATES: &[&str] = &["exchange", "hourly", "daily", "nightly", "heart", "frame", "bit", "baud", "sample", "refresh"];
+
+/// Upper bound and decimal places for a percentage column: `0..=1` when the sampled values
+/// are all fractions or the declared type can't hold more than one integer digit, otherwise
+/// `0..=100` (capped by a narrow `numeric(p,s)`). Decimal places follow the declared scale,
+/// then the sampled values.
+fn percentage_scale(col: &Column) -> (f64, usize) {
+    let sampled: Vec<&String> = col.distinct_values.iter().filter(|v| v.parse::<f64>().is_ok()).collect();
+    let int_digits = match (col.data_type.as_str(), col.numeric_precision, col.numeric_scale) {
+        ("numeric", Some(precision), Some(scale)) => Some((precision - scale).max(0)),
+        _ => None,
+    };
+    let fraction = int_digits.is_some_and(|d| d <= 1)
+        || (!sampled.is_empty() && sampled.iter().all(|v| v.parse::<f64>().is_ok_and(|n| (0.0..=1.0).contains(&n))));
+
+    let sampled_digits = sampled.iter()
+        .map(|v| v.split_once('.').map_or(0, |(_, frac)| frac.trim_end_matches('0').len()))
+        .max();
+    let digits = match col.numeric_scale {
+        Some(scale) if col.data_type == "numeric" => scale.max(0) as usize,
+        _ => sampled_digits.filter(|d| *d > 0).unwrap_or(if fraction { 4 } else { 2 }),
+    };
+
+    let max: f64 = if fraction { 1.0 } else { 100.0 };
+    // numeric(4,2) tops out at 99.99.
+    let max = match int_digits {
+        Some(d) if d < 3 => max.min(10f64.powi(d) - 10f64.powi(-(digits as i32))),
+        _ => max,
+    };
+    (max, digits)
+}
+
+/// Column-name words marking an address as inside the network, so it stays in private ranges.
+const INTERNAL_IP_HINTS: &[&str] = &["internal", "private", "local", "lan"];
+
+/// Special-purpose IPv4 blocks (RFC 6890 and friends) as (network, prefix length):
+/// private, loopback, link-local, CGNAT, documentation, benchmarking, multicast and reserved.
+const BOGON_V4: &[(u32, u32)] = &[
+    (0x0000_0000, 8), (0x0A00_0000, 8), (0x6440_0000, 10), (0x7F00_0000, 8),
+    (0xA9FE_0000, 16), (0xAC10_0000, 12), (0xC000_0000, 24), (0xC000_0200, 24),
+    (0xC058_6300, 24), (0xC0A8_0000, 16), (0xC612_0000, 15), (0xC633_6400, 24),
+    (0xCB00_7100, 24), (0xE000_0000, 4), (0xF000_0000, 4),
+];
+
+/// A version 4 UUID drawn from `rng`, so a seeded run repeats its UUIDs too.
+fn random_uuid(rng: &mut impl Rng) -> Uuid {
+    uuid::Builder::from_random_bytes(rng.gen()).into_uuid()
+}
+
+/// A globally routable IPv4 address: redrawn until it falls outside every bogon block,
+/// and never a .0 or .255 host.
+fn public_ipv4(rng: &mut impl Rng) -> String {
+    loop {
+        let ip: u32 = rng.gen();
+        let octets = ip.to_be_bytes();
+        if octets[3] == 0 || octets[3] == 255 {
+            continue;
+        }
+        if BOGON_V4.iter().all(|&(net, len)| (ip ^ net) >> (32 - len) != 0) {
+            return format!("{}.{}.{}.{}", octets[0], octets[1], octets[2], octets[3]);
+        }
+    }
+}
+
+// ====================================================================================
+// LOCALES
+// ====================================================================================
+
+/// Regional conventions for addresses and name order. Without `--locale` addresses stay
+/// US-style, names read given-then-family and places come from anywhere.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Locale {
+    EnUs,
+    EnGb,
+    DeDe,
+    FrFr,
+    EsEs,
+    ItIt,
+    NlNl,
+    PtBr,
+    JaJp,
+    HuHu,
+}
+
+/// Letters a UK postcode's inward code may end with (no C, I, K, M, O or V).
+const UK_POSTCODE_LETTERS: &[u8] = b"ABDEFGHJLNPQRSTUWXYZ";
+
+impl Locale {
+    /// The country whose places the locale's rows are set in, spelled as in GEO_ANCHORS.
+    fn country(self) -> &'static str {
+        match self {
+            Self::EnUs => "United States of America",
+            Self::EnGb => "United Kingdom",
+            Self::DeDe => "Germany",
+            Self::FrFr => "France",
+            Self::EsEs => "Spain",
+            Self::ItIt => "Italy",
+            Self::NlNl => "Netherlands",
+            Self::PtBr => "Brazil",
+            Self::JaJp => "Japan",
+            Self::HuHu => "Hungary",
+        }
+    }
+
+    /// Whether full names put the family name before the given name (`Yamada Taro`).
+    fn family_name_first(self) -> bool {
+        matches!(self, Self::JaJp | Self::HuHu)
+    }
+
+    /// A full name in the local order.
+    fn full_name(self, given: &str, family: &str) -> String {
+        if self.family_name_first() {
+            format!("{} {}", family, given)
+        } else {
+            format!("{} {}", given, family)
+        }
+    }
+
+    /// A street line in the local order: house number first or last, street type as a prefix
+    /// or suffix (`12 High Street`, `Lindenstraße 12`, `Rua das Flores, 12`, `2-11-3 Meguro`).
+    fn street_address(self, rng: &mut impl Rng) -> String {
+        let n = rng.gen_range(1..200);
+        let pick = |rng: &mut _, options: &[&'static str]| *options.choose(rng).unwrap();
+        match self {
+            Self::EnUs => {
+                let streets = ["Main St", "Oak Ave", "Maple Dr", "Pine Rd", "Elm St", "Park Blvd", "Broadway", "Market St"];
+                format!("{} {}", rng.gen_range(100..9999), pick(rng, &streets))
+            }
+            Self::EnGb => format!("{} {} {}", n,
+                pick(rng, &["High", "Station", "Church", "Victoria", "Mill", "Park", "King's", "Queen's", "London", "Manor"]),
+                pick(rng, &["Street", "Road", "Lane", "Avenue", "Close", "Crescent", "Gardens", "Way"])),
+            Self::DeDe => format!("{}{} {}",
+                pick(rng, &["Haupt", "Bahnhof", "Schul", "Garten", "Linden", "Berg", "Kirch", "Wald", "Goethe", "Schiller"]),
+                pick(rng, &["straße", "weg", "allee", "platz", "gasse", "ring"]), n),
+            Self::FrFr => format!("{} {} {}", n,
+                pick(rng, &["rue", "avenue", "boulevard", "place", "chemin", "allée"]),
+                pick(rng, &["de la Paix", "Victor Hugo", "Jean Jaurès", "de la République", "des Lilas", "du Moulin", "Pasteur", "de l'Église"])),
+            Self::EsEs => format!("{} {}, {}",
+                pick(rng, &["Calle", "Avenida", "Plaza", "Paseo", "Camino"]),
+                pick(rng, &["Mayor", "de la Constitución", "Real", "del Sol", "de Goya", "de Cervantes", "Nueva", "del Carmen"]), n),
+            Self::ItIt => format!("{} {}, {}",
+                pick(rng, &["Via", "Viale", "Piazza", "Corso", "Vicolo"]),
+                pick(rng, &["Roma", "Garibaldi", "Dante", "Mazzini", "Verdi", "Cavour", "della Repubblica", "XX Settembre"]), n),
+            Self::NlNl => format!("{}{} {}",
+                pick(rng, &["Kerk", "Dorps", "School", "Molen", "Wilhelmina", "Juliana", "Beatrix", "Stations"]),
+                pick(rng, &["straat", "weg", "laan", "plein", "gracht", "singel"]), n),
+            Self::PtBr => format!("{} {}, {}",
+                pick(rng, &["Rua", "Avenida", "Travessa", "Alameda", "Praça"]),
+                pick(rng, &["das Flores", "São João", "Sete de Setembro", "Tiradentes", "Dom Pedro II", "Santos Dumont", "da Liberdade", "XV de Novembro"]), n),
+            Self::JaJp => format!("{}-{}-{} {}", rng.gen_range(1..10), rng.gen_range(1..30), rng.gen_range(1..20),
+                pick(rng, &["Ginza", "Meguro", "Shibuya", "Shinjuku", "Umeda", "Namba", "Sakae", "Tenjin"])),
+            Self::HuHu => format!("{} {} {}.",
+                pick(rng, &["Kossuth Lajos", "Petőfi Sándor", "Rákóczi", "Ady Endre", "Dózsa György", "Széchenyi", "Arany János", "Béke"]),
+                pick(rng, &["utca", "út", "tér", "körút", "sor"]), n),
+        }
+    }
+
+    /// A postal code in the local format: UK `SW1A 1AA`, Dutch `1234 AB`, Brazilian
+    /// `01310-100`, Japanese `150-0002`, Hungarian `1052`, five digits elsewhere.
+    fn postal_code(self, rng: &mut impl Rng) -> String {
+        match self {
+            Self::EnUs => ZipCode().fake_with_rng(rng),
+            Self::EnGb => {
+                let area = ["SW", "SE", "EC", "WC", "N", "E", "W", "M", "B", "LS", "G", "EH", "BS", "CF", "L", "NE"].choose(rng).unwrap();
+                let letter = |rng: &mut _| *UK_POSTCODE_LETTERS.choose(rng).unwrap() as char;
+                format!("{}{} {}{}{}", area, rng.gen_range(1..20), rng.gen_range(0..10), letter(rng), letter(rng))
+            }
+            Self::DeDe => format!("{:05}", rng.gen_range(1067..99999)),
+            Self::FrFr => format!("{:02}{:03}", rng.gen_range(1..96), rng.gen_range(0..100) * 10),
+            Self::EsEs => format!("{:02}{:03}", rng.gen_range(1..53), rng.gen_range(0..1000)),
+            Self::ItIt => format!("{:05}", rng.gen_range(10..98200)),
+            Self::NlNl => {
+                let letters: String = (0..2).map(|_| rng.gen_range(b'A'..=b'Z') as char).collect();
+                format!("{} {}", rng.gen_range(1000..10000), letters)
+            }
+            Self::PtBr => format!("{:05}-{:03}", rng.gen_range(1000..100000), rng.gen_range(0..1000)),
+            Self::JaJp => format!("{:03}-{:04}", rng.gen_range(1..1000), rng.gen_range(0..10000)),
+            Self::HuHu => format!("{:04}", rng.gen_range(1011..9986)),
+        }
+    }
+
+    /// A phone number with the country calling code, grouped the local way.
+    fn phone_number(self, rng: &mut impl Rng) -> String {
+        fn digits(rng: &mut impl Rng, n: usize) -> String {
+            (0..n).map(|_| char::from(b'0' + rng.gen_range(0..10))).collect()
+        }
+        match self {
+            Self::EnUs => format!("+1-{}{}-{}-{}", rng.gen_range(2..10), digits(rng, 2), digits(rng, 3), digits(rng, 4)),
+            Self::EnGb => format!("+44 7{} {}", digits(rng, 3), digits(rng, 6)),
+            Self::DeDe => format!("+49 {} {}", ["30", "40", "69", "89", "221"].choose(rng).unwrap(), digits(rng, 7)),
+            Self::FrFr => format!("+33 {} {} {} {} {}", rng.gen_range(1..8), digits(rng, 2), digits(rng, 2), digits(rng, 2), digits(rng, 2)),
+            Self::EsEs => format!("+34 6{} {} {}", digits(rng, 2), digits(rng, 3), digits(rng, 3)),
+            Self::ItIt => format!("+39 3{} {}", digits(rng, 2), digits(rng, 7)),
+            Self::NlNl => format!("+31 6 {}", digits(rng, 8)),
+            Self::PtBr => format!("+55 {} 9{}-{}", ["11", "21", "31", "41", "51"].choose(rng).unwrap(), digits(rng, 4), digits(rng, 4)),
+            Self::JaJp => format!("+81 {}-{}-{}", ["3", "6", "52", "92"].choose(rng).unwrap(), digits(rng, 4), digits(rng, 4)),
+            Self::HuHu => format!("+36 {} {} {}", ["1", "20", "30", "70"].choose(rng).unwrap(), digits(rng, 3), digits(rng, 4)),
+        }
+    }
+}
+
+// ====================================================================================
+// FUZZ ENGINE
+// ====================================================================================
+
+struct Fuzzer;
+
+impl Fuzzer {
+    const TRICKY_STRINGS: [&'static str; 6] = [
+        "🦀🚀👩‍💻",
+        "مرحبا بالعالم",
+        "zero\u{200B}width\u{200D}joiner",
+        "e\u{0301}\u{0301}",
+        "O'Brien",
+        "   ",
+    ];
+    
+    /// Picks a boundary value that is still valid for the column's SQL type, or `None`
+    /// when the type has no interesting edges we know how to produce.
+    fn edge_value(col: &Column, rng: &mut impl Rng) -> Option<String> {
+        let dtype = col.data_type.to_lowercase();
+        let mut candidates: Vec<String> = Vec::new();
+        
+        if col.is_nullable {
+            candidates.push("NULL".to_string());
+        }
+        
+        if dtype.contains("char") || dtype == "text" {
+            let max_chars = col.max_length.map(|n| n as usize).unwrap_or(4096);
+            let mut strings: Vec<String> = vec![String::new(), "x".repeat(max_chars)];
+            strings.extend(Self::TRICKY_STRINGS.iter().map(|s| s.to_string()));
+            candidates.extend(strings.into_iter()
+                .filter(|s| s.chars().count() <= max_chars)
+                .map(|s| escape_sql_string(&s)));
+        } else if dtype == "smallint" {
+            candidates.extend(["-32768", "32767", "0"].map(String::from));
+        } else if dtype == "integer" {
+            candidates.extend(["-2147483648", "2147483647", "0"].map(String::from));
+        } else if dtype == "bigint" {
+            candidates.extend(["-9223372036854775808", "9223372036854775807", "0"].map(String::from));
+        } else if dtype == "numeric" {
+            let precision = col.numeric_precision.unwrap_or(18).max(1) as usize;
+            let scale = col.numeric_scale.unwrap_or(0).max(0) as usize;
+            let int_part = "9".repeat(precision.saturating_sub(scale).max(1));
+            let max = if scale > 0 { format!("{}.{}", int_part, "9".repeat(scale)) } else { int_part };
+            let max = if precision <= scale { format!("0.{}", "9".repeat(scale)) } else { max };
+            candidates.push(format!("-{}", max));
+            candidates.push(max);
+            candidates.push("0".to_string());
+        } else if dtype == "real" {
+            candidates.extend(["3.4e38", "-3.4e38", "1.2e-38", "0"].map(String::from));
+        } else if dtype == "double precision" {
+            candidates.extend(["1.7e308", "-1.7e308", "2.3e-308", "0"].map(String::from));
+        } else if dtype == "date" {
+            candidates.extend(["'0001-01-01'", "'1970-01-01'", "'2000-02-29'", "'2038-01-19'", "'9999-12-31'"].map(String::from));
+        } else if dtype == "timestamp with time zone" {
+            candidates.extend([
+                "'1970-01-01 00:00:00+00'",
+                "'1999-12-31 23:59:59-12'",
+                "'2038-01-19 03:14:07+14'",
+                "'2000-02-29 12:00:00+05:45'",
+            ].map(String::from));
+        } else if dtype.starts_with("timestamp") {
+            candidates.extend([
+                "'1970-01-01 00:00:00'",
+                "'1999-12-31 23:59:59'",
+                "'2038-01-19 03:14:07'",
+                "'2000-02-29 12:00:00'",
+            ].map(String::from));
+        }
+        
+        candidates.choose(rng).cloned()
     }
 }
 
@@ -554,26 +1487,29 @@ impl TopologicalSorter {
         let mut adjacency: HashMap<String, Vec<String>> = HashMap::new();
         
         for table in tables {
-            in_degree.insert(table.table_name.clone(), 0);
-            adjacency.insert(table.table_name.clone(), Vec::new());
+            in_degree.insert(table.qualified_name(), 0);
+            adjacency.insert(table.qualified_name(), Vec::new());
         }
         
         for table in tables {
+            let name = table.qualified_name();
             for fk in &table.foreign_keys {
-                if fk.ref_table == table.table_name || !adjacency.contains_key(&fk.ref_table) {
+                let parent = fk.ref_qualified_name();
+                if fk.deferred || parent == name || !adjacency.contains_key(&parent) {
                     continue;
                 }
-                adjacency.get_mut(&fk.ref_table).unwrap().push(table.table_name.clone());
-                *in_degree.get_mut(&table.table_name).unwrap() += 1;
+                adjacency.get_mut(&parent).unwrap().push(name.clone());
+                *in_degree.get_mut(&name).unwrap() += 1;
             }
         }
         
         let mut queue: VecDeque<String> = VecDeque::new();
         let mut sorted_names = Vec::new();
         
-        for (name, &degree) in &in_degree {
-            if degree == 0 {
-                queue.push_back(name.clone());
+        // Roots go in plan order, not the map's, so a seeded run sees the same table order.
+        for table in tables {
+            if in_degree[&table.qualified_name()] == 0 {
+                queue.push_back(table.qualified_name());
             }
         }
         
@@ -593,13 +1529,13 @@ impl TopologicalSorter {
         
         let sorted_set: HashSet<_> = sorted_names.iter().cloned().collect();
         for table in tables {
-            if !sorted_set.contains(&table.table_name) {
-                sorted_names.push(table.table_name.clone());
+            if !sorted_set.contains(&table.qualified_name()) {
+                sorted_names.push(table.qualified_name());
             }
         }
         
         let table_map: HashMap<_, _> = tables.iter()
-            .map(|t| (t.table_name.clone(), t.clone()))
+            .map(|t| (t.qualified_name(), t.clone()))
             .collect();
         
         sorted_names.iter()
@@ -609,157 +1545,2174 @@ impl TopologicalSorter {
 }
 
 // ====================================================================================
-// AI GENERATOR
+// ANALYSIS PLAN
 // ====================================================================================
 
-pub struct Generator {
-    tables: Vec<Table>,
-    pk_storage: HashMap<String, Vec<String>>,
+/// The analyzed model: every table in insertion order with the semantic type resolved
+/// for each of its columns. Serializes to the `--export-schema` JSON.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Plan {
+    pub tables: Vec<PlannedTable>,
+    /// Name of the analyzed database (never its URL or credentials), for provenance.
+    #[serde(default)]
+    pub source_database: Option<String>,
 }
 
-impl Generator {
-    pub fn new(tables: Vec<Table>) -> Self {
-        Self { 
-            tables,
-            pk_storage: HashMap::new(),
-        }
-    }
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PlannedTable {
+    pub sort_order: usize,
+    #[serde(flatten)]
+    pub table: Table,
+    /// Column name -> resolved semantic type
+    pub semantic_types: BTreeMap<String, SemanticType>,
+}
 
-    pub fn generate_sql_dump(&mut self, output_file: &str, row_count: usize) -> Result<()> {
-        let file = File::create(output_file)?;
-        let mut writer = BufWriter::new(file);
-        
-        writeln!(writer, "-- SynthDB Deep Learning AI Generator v14.0")?;
-        writeln!(writer, "-- Generated: {} (UTC)", Utc::now().format("%Y-%m-%d %H:%M:%S"))?;
-        writeln!(writer, "-- Rows per table: {}", row_count)?;
-        writeln!(writer, "-- AI: Deep Semantic Analysis, Pattern Recognition, Context Learning")?;
-        writeln!(writer, "BEGIN;")?;
-        writeln!(writer, "SET CONSTRAINTS ALL DEFERRED;\n")?;
+impl Plan {
+    /// Classifies every column of `tables`, which must already be in insertion order.
+    pub fn from_tables(tables: &[Table]) -> Self {
+        let tables = tables.iter()
+            .enumerate()
+            .map(|(idx, table)| PlannedTable {
+                sort_order: idx + 1,
+                semantic_types: table.columns.iter()
+                    .map(|col| (col.name.clone(), analyze_column(col, table)))
+                    .collect(),
+                table: table.clone(),
+            })
+            .collect();
+        Self { tables, source_database: None }
+    }
 
-        let sorted_tables = TopologicalSorter::sort(&self.tables);
-        
-        println!("🧠 Deep Learning Analysis Complete:");
-        for (idx, table) in sorted_tables.iter().enumerate() {
-            println!("   {}. {} ({} columns analyzed)", idx + 1, table.table_name, table.columns.len());
+    /// Keeps only the tables named in `names` (bare or schema-qualified), inserted in that order
+    /// instead of the topological one. An FK whose parent now comes later is deferred and
+    /// back-patched like a cycle edge; an FK whose parent was left out is only reported.
+    pub fn select_tables(&mut self, names: &[String]) -> Result<()> {
+        let mut remaining: Vec<Option<PlannedTable>> = std::mem::take(&mut self.tables).into_iter().map(Some).collect();
+        for name in names {
+            let idx = remaining.iter()
+                .position(|p| p.as_ref().is_some_and(|p| p.table.qualified_name() == *name || p.table.table_name == *name));
+            match idx {
+                Some(idx) => self.tables.push(remaining[idx].take().expect("position only matches remaining tables")),
+                None if self.tables.iter().any(|p| p.table.qualified_name() == *name || p.table.table_name == *name) => {
+                    bail!("--tables names '{}' more than once", name)
+                }
+                None => bail!("--tables names '{}', which is not in the schema", name),
+            }
         }
-        println!();
-
-        for table in sorted_tables {
-            println!("📊 Generating semantic data for: {}", table.table_name);
-            
-            writeln!(writer, "-- Data for {}", table.table_name)?;
-            
-            let col_names: Vec<String> = table.columns.iter().map(|c| c.name.clone()).collect();
-            writeln!(writer, "INSERT INTO {} ({}) VALUES", table.table_name, col_names.join(", "))?;
-
-            let mut table_pks = Vec::new();
-
-            for row_idx in 0..row_count {
-                let row_values = self.generate_intelligent_row(&table, row_idx);
 
-                for (idx, col) in table.columns.iter().enumerate() {
-                    let semantic_type = self.analyze_column(col, &table);
-                    if semantic_type == SemanticType::PrimaryKey {
-                        let pk = row_values[idx].trim_matches('\'').to_string();
-                        table_pks.push(pk);
-                        break;
+        let positions: HashMap<String, usize> = self.tables.iter()
+            .enumerate()
+            .map(|(idx, p)| (p.table.qualified_name(), idx))
+            .collect();
+        for (idx, planned) in self.tables.iter_mut().enumerate() {
+            planned.sort_order = idx + 1;
+            let table = &mut planned.table;
+            let name = table.qualified_name();
+            for fk in table.foreign_keys.iter_mut().filter(|fk| !fk.deferred) {
+                let parent = fk.ref_qualified_name();
+                match positions.get(&parent) {
+                    Some(&p) if p > idx => {
+                        status!("⚠️ --tables puts {} before its parent {}: deferring {}.{} (filled in by UPDATE after all inserts)",
+                            name, parent, table.table_name, fk.column);
+                        fk.deferred = true;
+                        let nullable = table.columns.iter().any(|c| c.name == fk.column && c.is_nullable);
+                        if !nullable && !fk.deferrable && !fk.inferred {
+                            status!("⚠️ {}.{} is NOT NULL and NOT DEFERRABLE: its placeholder values will be rejected on import",
+                                table.table_name, fk.column);
+                        }
                     }
+                    Some(_) => {}
+                    None => status!("⚠️ {}.{} references {}, which --tables leaves out", name, fk.column, parent),
                 }
-
-                let sep = if row_idx == row_count - 1 { ";" } else { "," };
-                writeln!(writer, "({}){}", row_values.join(", "), sep)?;
             }
-
-            self.pk_storage.insert(table.table_name.clone(), table_pks);
-            writeln!(writer)?;
         }
-        
-        writeln!(writer, "COMMIT;")?;
-        writer.flush()?;
-        
-        println!("\n✅ AI-Generated SQL: {}", output_file);
-        println!("📊 Tables: {} | Total rows: {}", 
-            self.pk_storage.len(), 
-            self.pk_storage.values().map(|v| v.len()).sum::<usize>()
-        );
-        
         Ok(())
     }
-    
-    fn analyze_column(&self, col: &Column, table: &Table) -> SemanticType {
-        let fk = table.foreign_keys.iter().find(|f| f.column == col.name);
-        DeepAnalyzer::analyze_field_intelligence(
-            &col.name,
-            &col.data_type,
-            fk.is_some(),
-            fk.map(|f| f.ref_table.as_str()),
-            &col.distinct_values,
-            &table.table_name
-        )
+
+    /// Sizes every table named in `fixtures` (bare or schema-qualified) to its fixture rows and
+    /// returns the fixtures keyed by qualified name. Fails on a table outside the plan, a
+    /// column it doesn't have, or a generated column, which can't be inserted into.
+    pub fn apply_fixtures(&mut self, fixtures: HashMap<String, FixtureRows>) -> Result<HashMap<String, FixtureRows>> {
+        let mut applied = HashMap::new();
+        for (name, rows) in fixtures {
+            let table = self.tables.iter_mut()
+                .map(|p| &mut p.table)
+                .find(|t| t.qualified_name() == name || t.table_name == name)
+                .with_context(|| format!("--fixtures names '{}', which is not among the generated tables", name))?;
+            for column in rows.iter().flat_map(|row| row.keys()) {
+                match table.columns.iter().find(|c| &c.name == column) {
+                    None => bail!("--fixtures row for '{}' has column '{}', which the table doesn't have", name, column),
+                    Some(col) if col.is_generated => bail!("--fixtures row for '{}' sets '{}', a generated column", name, column),
+                    Some(_) => {}
+                }
+            }
+            table.row_count = Some(rows.len());
+            if applied.insert(table.qualified_name(), rows).is_some() {
+                bail!("--fixtures names {} more than once", table.qualified_name());
+            }
+        }
+        Ok(applied)
     }
-    
-    fn generate_intelligent_row(&self, table: &Table, row_idx: usize) -> Vec<String> {
-        let mut context = ContextEngine::new();
-        let mut temp_values: HashMap<String, String> = HashMap::new();
-        
-        let mut column_semantics: Vec<(Column, SemanticType)> = table.columns.iter()
-            .map(|col| {
-                let semantic = self.analyze_column(col, table);
-                (col.clone(), semantic)
+
+    /// Columns the classifier left on a generic fallback (`TextValue`, `IntegerValue`,
+    /// `DecimalValue`) as `(table, column, data_type)`: the candidates for an override.
+    /// Generated columns are skipped since they're never written.
+    pub fn unclassified_columns(&self) -> Vec<(String, String, String)> {
+        self.tables.iter()
+            .flat_map(|planned| {
+                let table = &planned.table;
+                table.columns.iter()
+                    .filter(|col| !col.is_generated)
+                    .filter(|col| matches!(planned.semantic_types.get(&col.name),
+                        Some(SemanticType::TextValue | SemanticType::IntegerValue | SemanticType::DecimalValue)))
+                    .map(|col| (table.qualified_name(), col.name.clone(), col.data_type.clone()))
             })
-            .collect();
-        
-        column_semantics.sort_by_key(|(_, sem)| std::cmp::Reverse(DeepAnalyzer::get_generation_priority(sem)));
-        
-        for (col, semantic) in &column_semantics {
-            let value = self.generate_by_semantic(semantic, col, &context, row_idx);
-            self.update_context(&col.name, &value, semantic, &mut context);
-            temp_values.insert(col.name.clone(), value);
-        }
-        
-        table.columns.iter()
-            .map(|col| temp_values.get(&col.name).unwrap().clone())
             .collect()
     }
-    
-    fn generate_by_semantic(&self, semantic: &SemanticType, col: &Column, ctx: &ContextEngine, row_idx: usize) -> String {
-        let mut rng = rand::thread_rng();
-        
-        match semantic {
-            SemanticType::ForeignKey(ref_table) => {
-                let value = self.get_fk_value(ref_table, &col.data_type);
-                if value == "NULL" {
-                    return self.generate_default(&col.data_type, row_idx);
-                }
-                value
-            },
-            
-            SemanticType::PrimaryKey => {
-                if col.data_type.contains("uuid") {
-                    format!("'{}'", Uuid::new_v4())
-                } else {
-                    (row_idx + 1).to_string()
-                }
-            },
-            
-            SemanticType::UUID => format!("'{}'", Uuid::new_v4()),
-            SemanticType::BooleanValue => rng.gen_bool(0.75).to_string(),
-            
-            SemanticType::FirstName => format!("'{}'", FirstName().fake::<String>().replace("'", "''")),
-            SemanticType::LastName => format!("'{}'", LastName().fake::<String>().replace("'", "''")),
-            SemanticType::FullName => {
-                if let (Some(f), Some(l)) = (ctx.get("first_name"), ctx.get("last_name")) {
-                    format!("'{} {}'", f, l)
-                } else {
-                    format!("'{}'", Name().fake::<String>().replace("'", "''"))
-                }
-            },
+
+    /// Loads a plan written by `write_json`, including any hand-made semantic type corrections.
+    pub fn read_json(path: &str) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("cannot open plan file '{}'", path))?;
+        serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("'{}' is not a valid analysis plan", path))
+    }
+
+    pub fn write_json(&self, path: &str) -> Result<()> {
+        let file = File::create(path)?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)?;
+        Ok(())
+    }
+}
+
+/// Resolves a column's semantic type from its name, type, comment, samples and key constraints.
+fn analyze_column(col: &Column, table: &Table) -> SemanticType {
+    // An array is classified by its element; generate_array repeats that element.
+    if let Some(element) = col.array_element() {
+        return analyze_column(&element, table);
+    }
+    // PostGIS types only show up as USER-DEFINED; the declared type says what they hold.
+    if postgis_point(&col.sql_type).is_some() {
+        return SemanticType::GeoPoint;
+    }
+    let fk = table.foreign_keys.iter().find(|f| f.column == col.name);
+    // A user-defined enum only accepts its own labels, whatever the column is called.
+    if fk.is_none() && !col.enum_labels.is_empty() {
+        return SemanticType::EnumLabel;
+    }
+    let declared_pk = (!table.primary_keys.is_empty()).then(|| table.primary_keys.contains(&col.name));
+    let semantic = DeepAnalyzer::analyze_field_intelligence(
+        &col.name,
+        &col.data_type,
+        fk.map(|f| f.ref_qualified_name()).as_deref(),
+        declared_pk,
+        col.comment.as_deref(),
+        &col.distinct_values,
+        &table.table_name
+    );
+    // A country column too short for a name holds its ISO code; so does a char(2)/char(3) `iso*` column.
+    let code_sized = col.max_length.is_some_and(|len| len <= 3);
+    let iso_named = col.name.to_lowercase().split('_').any(|p| p.starts_with("iso"));
+    match semantic {
+        SemanticType::Country if code_sized => SemanticType::CountryCode,
+        SemanticType::TextValue | SemanticType::ReferenceID if code_sized && iso_named => SemanticType::CountryCode,
+        _ if fk.is_none() && is_coordinate_pair(col) => SemanticType::CoordinatePair,
+        other => other,
+    }
+}
+
+/// Names of text columns that hold a whole `"lat,long"` pair, alone or as a `_` suffix.
+const COORDINATE_PAIR_NAMES: &[&str] = &[
+    "coordinates", "coords", "coord", "latlng", "latlon", "latlong", "lat_lng", "lat_lon", "lat_long", "geo", "geolocation", "gps",
+];
+
+/// A text column named like a coordinate pair, or whose sampled values all are `lat,long` decimals.
+/// A bare `location` counts only when nothing was sampled to say otherwise; `pickup_location`
+/// and the like usually hold place names.
+fn is_coordinate_pair(col: &Column) -> bool {
+    let text = col.data_type == "text" || col.data_type.contains("char");
+    let name = col.name.to_lowercase();
+    let named = COORDINATE_PAIR_NAMES.iter().any(|n| name == *n || name.ends_with(&format!("_{}", n)))
+        || (name == "location" && col.distinct_values.is_empty());
+    let sampled = !col.distinct_values.is_empty()
+        && col.distinct_values.iter().all(|v| v.contains('.') && coordinate_pair(v).is_some());
+    text && (named || sampled)
+}
+
+/// Parses `"lat,long"` (spaces allowed) with both parts in range.
+fn coordinate_pair(value: &str) -> Option<(f64, f64)> {
+    let (lat, lon) = value.split_once(',')?;
+    let (lat, lon): (f64, f64) = (lat.trim().parse().ok()?, lon.trim().parse().ok()?);
+    ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lon)).then_some((lat, lon))
+}
+
+// ====================================================================================
+// RUN MANIFEST
+// ====================================================================================
+
+/// Provenance for a generated dataset, written as the `--manifest` sidecar JSON.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub synthdb_version: String,
+    /// RFC 3339 timestamp of the start of generation
+    pub generated_at: String,
+    pub dialect: String,
+    pub format: String,
+    /// RNG seed of the run; passing it back as `--seed` repeats the run's random draws.
+    pub seed: Option<u64>,
+    pub source_database: Option<String>,
+    /// `schema.table` -> rows generated
+    pub tables: BTreeMap<String, usize>,
+    /// `schema.table` -> largest integer primary key written, where `--append` resumes numbering
+    #[serde(default)]
+    pub last_keys: BTreeMap<String, u64>,
+}
+
+impl Manifest {
+    pub fn read_json(path: &str) -> Result<Self> {
+        let file = File::open(path).with_context(|| format!("cannot open manifest '{}'", path))?;
+        serde_json::from_reader(BufReader::new(file))
+            .with_context(|| format!("'{}' is not a valid manifest", path))
+    }
+
+    /// Folds in the manifest of the dump this run appended to: row counts add up and each
+    /// table keeps the larger of the two last keys.
+    pub fn continue_from(&mut self, previous: Manifest) {
+        for (table, rows) in previous.tables {
+            *self.tables.entry(table).or_default() += rows;
+        }
+        for (table, key) in previous.last_keys {
+            let last = self.last_keys.entry(table).or_default();
+            *last = (*last).max(key);
+        }
+    }
+
+    pub fn write_json(&self, path: &str) -> Result<()> {
+        let file = File::create(path).with_context(|| format!("cannot create manifest '{}'", path))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)?;
+        Ok(())
+    }
+}
+
+// ====================================================================================
+// GENERATION STATS
+// ====================================================================================
+
+/// What each column came out as, written as the `--stats` JSON.
+#[derive(Debug, Default, Serialize)]
+pub struct GenerationStats {
+    /// `schema.table` -> its rows and columns, in insertion order of the columns
+    pub tables: BTreeMap<String, TableStats>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TableStats {
+    pub rows: usize,
+    pub columns: Vec<ColumnStats>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ColumnStats {
+    pub column: String,
+    pub semantic_type: SemanticType,
+    pub nulls: usize,
+    pub distinct: usize,
+    /// Smallest and largest value, for columns written as bare numbers
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    #[serde(skip)]
+    index: usize,
+    /// Hashes of the values seen, so distinct counts don't keep every value around
+    #[serde(skip)]
+    seen: HashSet<u64>,
+}
+
+impl TableStats {
+    fn record(&mut self, row_values: &[String]) {
+        self.rows += 1;
+        for col in &mut self.columns {
+            let value = &row_values[col.index];
+            if value == "NULL" {
+                col.nulls += 1;
+                continue;
+            }
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            if col.seen.insert(hasher.finish()) {
+                col.distinct += 1;
+            }
+            if let Ok(number) = value.parse::<f64>() {
+                col.min = Some(col.min.map_or(number, |min| min.min(number)));
+                col.max = Some(col.max.map_or(number, |max| max.max(number)));
+            }
+        }
+    }
+}
+
+impl GenerationStats {
+    pub fn write_json(&self, path: &str) -> Result<()> {
+        let file = File::create(path).with_context(|| format!("cannot create stats file '{}'", path))?;
+        serde_json::to_writer_pretty(BufWriter::new(file), self)?;
+        Ok(())
+    }
+}
+
+// ====================================================================================
+// COLUMN OVERRIDES
+// ====================================================================================
+
+/// A user-pinned value source for one column, replacing semantic generation entirely, or
+/// the locale its semantic values are generated in.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnOverride {
+    /// The same value in every row (`constant:<value>`)
+    Constant(String),
+    /// `start`, `start + 1`, ... by row (`sequence:<start>`)
+    Sequence(i64),
+    /// A JSON document conforming to the schema in a file (`jsonschema:<path>`)
+    JsonSchema(serde_json::Value),
+    /// Names, addresses and phone numbers in this locale instead of `--locale`'s (`locale:<code>`)
+    Locale(Locale),
+}
+
+impl ColumnOverride {
+    pub fn parse(directive: &str) -> Result<Self> {
+        let (kind, arg) = directive.split_once(':')
+            .with_context(|| format!("expected 'constant:<value>', 'sequence:<start>', 'jsonschema:<path>' or 'locale:<code>', got '{}'", directive))?;
+        match kind.trim() {
+            "constant" => Ok(Self::Constant(arg.to_string())),
+            "sequence" => {
+                let start = arg.trim().parse()
+                    .with_context(|| format!("sequence start '{}' is not an integer", arg))?;
+                Ok(Self::Sequence(start))
+            }
+            "jsonschema" => {
+                let path = arg.trim();
+                let file = File::open(path).with_context(|| format!("cannot open JSON Schema '{}'", path))?;
+                let schema = serde_json::from_reader(BufReader::new(file))
+                    .with_context(|| format!("'{}' is not valid JSON", path))?;
+                Ok(Self::JsonSchema(schema))
+            }
+            "locale" => {
+                let locale = <Locale as clap::ValueEnum>::from_str(arg.trim(), true)
+                    .ok().with_context(|| format!("unknown locale '{}'", arg.trim()))?;
+                Ok(Self::Locale(locale))
+            }
+            other => bail!("unknown override '{}': expected constant, sequence, jsonschema or locale", other),
+        }
+    }
+
+    /// The SQL literal for `row_idx`; numbers and booleans stay bare for matching column types.
+    /// `None` for a locale, which leaves the value to semantic generation.
+    fn value(&self, col: &Column, row_idx: usize, rng: &mut impl Rng) -> Option<String> {
+        let raw = match self {
+            Self::Constant(value) => value.clone(),
+            Self::Sequence(start) => (start + row_idx as i64).to_string(),
+            Self::JsonSchema(schema) => {
+                let document = json_from_schema(schema, schema, rng, 0);
+                return Some(escape_sql_string(&document.to_string()));
+            }
+            Self::Locale(_) => return None,
+        };
+        let dtype = col.data_type.to_lowercase();
+        let numeric = ["int", "numeric", "decimal", "real", "double"].iter().any(|t| dtype.contains(t));
+        Some(if raw.eq_ignore_ascii_case("null") {
+            "NULL".to_string()
+        } else if (numeric && raw.parse::<f64>().is_ok()) || (dtype == "boolean" && raw.parse::<bool>().is_ok()) {
+            raw
+        } else {
+            escape_sql_string(&raw)
+        })
+    }
+}
+
+/// Nesting beyond which optional properties and array items are left out, so recursive
+/// schemas (a tree node whose children are nodes) still end.
+const JSON_SCHEMA_MAX_DEPTH: usize = 6;
+
+/// Builds a document matching `schema`: `$ref` (local, `#/...`), `const`, `enum`, `oneOf`/`anyOf`
+/// (one branch), `allOf` (properties merged), and per type `properties`/`required`,
+/// `items`/`minItems`/`maxItems`, `minimum`/`maximum`, `minLength`/`maxLength` and the common
+/// string formats. Required properties are always present, optional ones half the time;
+/// keywords outside this list (e.g. `pattern`) are ignored.
+fn json_from_schema(schema: &serde_json::Value, root: &serde_json::Value, rng: &mut impl Rng, depth: usize) -> serde_json::Value {
+    use serde_json::{json, Map, Value};
+
+    if let Some(target) = schema.get("$ref").and_then(Value::as_str) {
+        let resolved = target.strip_prefix('#').and_then(|pointer| root.pointer(pointer));
+        return match resolved {
+            Some(resolved) => json_from_schema(resolved, root, rng, depth + 1),
+            None => Value::Null,
+        };
+    }
+    if let Some(value) = schema.get("const") {
+        return value.clone();
+    }
+    if let Some(choice) = schema.get("enum").and_then(Value::as_array).and_then(|values| values.choose(rng)) {
+        return choice.clone();
+    }
+    for key in ["oneOf", "anyOf"] {
+        if let Some(branch) = schema.get(key).and_then(Value::as_array).and_then(|branches| branches.choose(rng)) {
+            return json_from_schema(branch, root, rng, depth + 1);
+        }
+    }
+    if let Some(parts) = schema.get("allOf").and_then(Value::as_array) {
+        let mut merged = Map::new();
+        for part in parts {
+            if let Value::Object(fields) = json_from_schema(part, root, rng, depth + 1) {
+                merged.extend(fields);
+            }
+        }
+        return Value::Object(merged);
+    }
+
+    // `type` may list several; null is only picked when it's the sole option.
+    let kind = match schema.get("type") {
+        Some(Value::String(kind)) => kind.as_str(),
+        Some(Value::Array(kinds)) => {
+            let kinds: Vec<&str> = kinds.iter().filter_map(Value::as_str).collect();
+            kinds.iter().copied().filter(|k| *k != "null").collect::<Vec<_>>().choose(rng).copied()
+                .or(kinds.first().copied())
+                .unwrap_or("string")
+        }
+        _ if schema.get("properties").is_some() => "object",
+        _ if schema.get("items").is_some() => "array",
+        _ => "string",
+    };
+    let bound = |key: &str| schema.get(key).and_then(Value::as_f64);
+
+    match kind {
+        "object" => {
+            let required: Vec<&str> = schema.get("required").and_then(Value::as_array)
+                .map(|names| names.iter().filter_map(Value::as_str).collect())
+                .unwrap_or_default();
+            let mut object = Map::new();
+            if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                for (name, property) in properties {
+                    let keep = required.contains(&name.as_str()) || (depth < JSON_SCHEMA_MAX_DEPTH && rng.gen_bool(0.5));
+                    if keep {
+                        object.insert(name.clone(), json_from_schema(property, root, rng, depth + 1));
+                    }
+                }
+            }
+            Value::Object(object)
+        }
+        "array" => {
+            let min = bound("minItems").unwrap_or(0.0) as usize;
+            let max = if depth < JSON_SCHEMA_MAX_DEPTH { bound("maxItems").map_or(min.max(3), |m| m as usize) } else { min };
+            let items = schema.get("items").cloned().unwrap_or(json!({}));
+            let count = rng.gen_range(min..=max.max(min));
+            Value::Array((0..count).map(|_| json_from_schema(&items, root, rng, depth + 1)).collect())
+        }
+        "integer" => {
+            let min = bound("minimum").unwrap_or(0.0).ceil() as i64;
+            let max = bound("maximum").map_or(min.max(0) + 1000, |m| m.floor() as i64);
+            json!(rng.gen_range(min..=max.max(min)))
+        }
+        "number" => {
+            let min = bound("minimum").unwrap_or(0.0);
+            let max = bound("maximum").unwrap_or(min.max(0.0) + 1000.0).max(min);
+            json!(((min + rng.gen::<f64>() * (max - min)) * 100.0).round() / 100.0)
+        }
+        "boolean" => json!(rng.gen_bool(0.5)),
+        "null" => Value::Null,
+        _ => {
+            let text = match schema.get("format").and_then(Value::as_str) {
+                Some("date-time") => (Utc::now() - Duration::seconds(rng.gen_range(0..3 * 365 * 86400))).to_rfc3339(),
+                Some("date") => (Utc::now() - Duration::days(rng.gen_range(0..3 * 365))).format("%Y-%m-%d").to_string(),
+                Some("email") => format!("{}@example.com", FirstName().fake_with_rng::<String, _>(rng).to_lowercase()),
+                Some("uuid") => random_uuid(rng).to_string(),
+                Some("uri") | Some("url") => format!("https://example.com/{}", rng.gen_range(1000..9999)),
+                Some("ipv4") => format!("10.{}.{}.{}", rng.gen_range(0..256), rng.gen_range(0..256), rng.gen_range(1..255)),
+                _ => Words(1..4).fake_with_rng::<Vec<String>, _>(rng).join(" "),
+            };
+            let min = bound("minLength").unwrap_or(0.0) as usize;
+            let max = bound("maxLength").map_or(usize::MAX, |m| m as usize);
+            let mut text = text.chars().take(max).collect::<String>().trim_end().to_string();
+            while text.chars().count() < min {
+                text.push(rng.gen_range(b'a'..=b'z') as char);
+            }
+            json!(text)
+        }
+    }
+}
+
+/// Reads an overrides file: a JSON object mapping `table.column`, `schema.table.column`
+/// or `*.column` (every table) to a directive, e.g. `{"*.tenant_id": "constant:42"}` or
+/// `{"users.jp_name": "locale:ja-jp"}`.
+pub fn load_overrides(path: &str) -> Result<HashMap<String, ColumnOverride>> {
+    let file = File::open(path).with_context(|| format!("cannot open overrides file '{}'", path))?;
+    let directives: HashMap<String, String> = serde_json::from_reader(BufReader::new(file))
+        .with_context(|| format!("'{}' must be a JSON object of \"table.column\": \"directive\"", path))?;
+    directives.into_iter()
+        .map(|(key, directive)| {
+            let parsed = ColumnOverride::parse(&directive).with_context(|| format!("bad override for '{}'", key))?;
+            Ok((key, parsed))
+        })
+        .collect()
+}
+
+// ====================================================================================
+// FIXTURES
+// ====================================================================================
+
+/// Hand-written rows for one table, column name to value; columns a row leaves out are
+/// generated as usual.
+pub type FixtureRows = Vec<serde_json::Map<String, serde_json::Value>>;
+
+/// Reads a fixtures file, JSON or (by a `.yaml`/`.yml` extension) YAML, mapping `table` or
+/// `schema.table` to its rows, e.g. `{"roles": [{"id": 1, "name": "admin"}]}`.
+pub fn load_fixtures(path: &str) -> Result<HashMap<String, FixtureRows>> {
+    let file = File::open(path).with_context(|| format!("cannot open fixtures file '{}'", path))?;
+    let reader = BufReader::new(file);
+    let expected = || format!("'{}' must map table names to lists of {{\"column\": value}} rows", path);
+    if path.ends_with(".yaml") || path.ends_with(".yml") {
+        serde_yaml::from_reader(reader).with_context(expected)
+    } else {
+        serde_json::from_reader(reader).with_context(expected)
+    }
+}
+
+/// The SQL literal for a fixture value: numbers and booleans stay bare for matching column
+/// types, a list fills an array column, and anything else is quoted as text (objects as JSON).
+fn fixture_literal(value: &serde_json::Value, col: &Column) -> String {
+    use serde_json::Value;
+    let dtype = col.data_type.to_lowercase();
+    let numeric = ["int", "numeric", "decimal", "real", "double"].iter().any(|t| dtype.contains(t));
+    match value {
+        Value::Null => "NULL".to_string(),
+        Value::Number(n) if numeric => n.to_string(),
+        Value::Bool(b) if dtype == "boolean" => b.to_string(),
+        Value::String(s) => escape_sql_string(s),
+        Value::Array(items) if col.array_element().is_some() => {
+            let items: Vec<String> = items.iter()
+                .map(|item| match item {
+                    Value::Null => "NULL".to_string(),
+                    Value::String(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+                    other => format!("\"{}\"", other.to_string().replace('\\', "\\\\").replace('"', "\\\"")),
+                })
+                .collect();
+            escape_sql_string(&format!("{{{}}}", items.join(",")))
+        }
+        other => escape_sql_string(&other.to_string()),
+    }
+}
+
+// ====================================================================================
+// CUSTOM PROVIDERS
+// ====================================================================================
+
+/// A library-registered value source for columns no built-in semantic type covers
+/// (medical codes, VINs, ...). See [`Generator::register_provider`].
+pub trait Provider {
+    /// Returns a SQL literal for `col` (quote text with [`crate::sql::escape_sql_string`]).
+    fn generate(&self, col: &Column, ctx: &ContextEngine, rng: &mut dyn RngCore) -> String;
+}
+
+/// Which columns a registered [`Provider`] serves.
+#[derive(Debug, Clone)]
+pub enum ProviderMatch {
+    /// Column names matching the pattern, in any table
+    ColumnName(Regex),
+    /// Columns classified as this semantic type
+    Semantic(SemanticType),
+}
+
+impl ProviderMatch {
+    fn matches(&self, col: &Column, semantic: &SemanticType) -> bool {
+        match self {
+            Self::ColumnName(pattern) => pattern.is_match(&col.name),
+            Self::Semantic(wanted) => wanted == semantic,
+        }
+    }
+}
+
+/// Called with every generated row: the table, its values as SQL literals in column order,
+/// and the row index. See [`Generator::set_row_hook`].
+pub type RowHook = Box<dyn Fn(&Table, &mut Vec<String>, usize)>;
+
+// ====================================================================================
+// AI GENERATOR
+// ====================================================================================
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// A single SQL file of INSERT statements
+    #[default]
+    Sql,
+    /// One CSV file per table inside the output directory
+    Csv,
+}
+
+/// Encoder wrapped around the SQL dump.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum Compression {
+    /// `.gz`, readable by gunzip and `psql` via `zcat`
+    Gzip,
+    /// `.zst`, faster and smaller than gzip
+    Zstd,
+}
+
+impl Compression {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Gzip => "gz",
+            Self::Zstd => "zst",
+        }
+    }
+}
+
+/// The file a dump asked for as `output` really goes to: with `--compress`, the encoder's
+/// extension is appended unless the name already carries it. Stdout (`-`) stays as is.
+pub fn dump_path(output: &str, compress: Option<Compression>) -> String {
+    match compress {
+        Some(c) if output != "-" && !output.ends_with(&format!(".{}", c.extension())) => {
+            format!("{}.{}", output, c.extension())
+        }
+        _ => output.to_string(),
+    }
+}
+
+/// Which parts of the dump to write, mirroring `pg_dump --section`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum Section {
+    /// INSERT statements only
+    #[default]
+    Data,
+    /// CREATE TABLE / foreign key DDL only
+    Schema,
+    /// DDL followed by data
+    All,
+}
+
+/// How child rows spread their references over the parent table's rows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum FkDistribution {
+    /// Every parent is equally likely; ON DELETE CASCADE children first give every parent one
+    #[default]
+    Uniform,
+    /// A few parents own most of the children
+    Zipf,
+    /// Each parent is used at most once per FK column while parents last
+    OneToOne,
+}
+
+/// The forest a self-referencing FK builds: the first `roots` rows are top-level, and no
+/// branch grows deeper than `max_depth` levels (a root is level 1).
+#[derive(Debug, Clone, Copy)]
+pub struct TreeShape {
+    pub roots: usize,
+    pub max_depth: Option<usize>,
+}
+
+/// Rows of one self-referencing FK seen so far: each key's level, and the keys that may
+/// still take children.
+#[derive(Default)]
+struct TreeLevels {
+    levels: HashMap<String, usize>,
+    open: Vec<String>,
+}
+
+/// What a generated INSERT does when a row with the same primary key already exists.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum OnConflict {
+    /// `ON CONFLICT DO NOTHING`: keep the existing row
+    Skip,
+    /// `ON CONFLICT DO UPDATE`: overwrite the existing row's non-key columns
+    Update,
+}
+
+/// Where a SQL dump goes: the file or stdout, through the `--compress` encoder if any.
+enum DumpSink {
+    Plain(Box<dyn Write>),
+    Gzip(flate2::write::GzEncoder<Box<dyn Write>>),
+    Zstd(zstd::Encoder<'static, Box<dyn Write>>),
+}
+
+impl DumpSink {
+    fn new(inner: Box<dyn Write>, compress: Option<Compression>) -> io::Result<Self> {
+        Ok(match compress {
+            None => Self::Plain(inner),
+            Some(Compression::Gzip) => Self::Gzip(flate2::write::GzEncoder::new(inner, flate2::Compression::default())),
+            Some(Compression::Zstd) => Self::Zstd(zstd::Encoder::new(inner, 0)?),
+        })
+    }
+
+    /// Writes the encoder's trailer; dropping the sink instead would hide a failed final write.
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Plain(mut inner) => inner.flush(),
+            Self::Gzip(encoder) => encoder.finish()?.flush(),
+            Self::Zstd(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for DumpSink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(inner) => inner.write(buf),
+            Self::Gzip(encoder) => encoder.write(buf),
+            Self::Zstd(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(inner) => inner.flush(),
+            Self::Gzip(encoder) => encoder.flush(),
+            Self::Zstd(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Passes writes through to `inner`, counting the bytes for `--max-size`.
+struct CountingWriter<W> {
+    inner: W,
+    written: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// The columns one table's INSERTs list, and the `ON CONFLICT` clause that ends each statement.
+struct InsertTarget<'a> {
+    table: &'a Table,
+    included: Vec<usize>,
+    conflict: String,
+}
+
+/// Bytes `--max-size` counts for one table besides its rows' values.
+struct TableFootprint {
+    /// `-- Data for` comment and the BEGIN/SAVEPOINT that opens the table.
+    opening: String,
+    /// COMMIT/RELEASE SAVEPOINT and the blank line that close it.
+    closing: String,
+    /// `INSERT INTO ... VALUES` line starting each batch.
+    insert_header: u64,
+    /// What the table adds after the data whatever its rows: the comment (and transaction)
+    /// around each deferred FK's back-patch, and its sequence reset.
+    trailer: u64,
+    /// Back-patch UPDATEs for one row, without the key literals they carry.
+    backpatch_row: u64,
+    backpatch_fks: u64,
+    pk_index: Option<usize>,
+}
+
+impl TableFootprint {
+    /// Opening and closing lines, and the trailer.
+    fn fixed_size(&self) -> u64 {
+        (self.opening.len() + self.closing.len()) as u64 + self.trailer
+    }
+
+    /// Bytes one row adds to the INSERT, with the header when it starts a batch, plus its
+    /// back-patch UPDATEs.
+    fn row_size(&self, target: &InsertTarget, row_values: &[String], row_idx: usize, batch_size: usize) -> u64 {
+        let values: usize = target.included.iter().map(|&idx| row_values[idx].len() + 2).sum();
+        let header = if row_idx.is_multiple_of(batch_size) { self.insert_header } else { 0 };
+        header + (values + target.conflict.len() + 2) as u64 + self.backpatch_size(row_values)
+    }
+
+    /// The back-patch UPDATEs written for this row at the end of the dump.
+    fn backpatch_size(&self, row_values: &[String]) -> u64 {
+        let key = self.pk_index.map_or(0, |idx| row_values[idx].len() as u64);
+        self.backpatch_fks * 2 * key + self.backpatch_row
+    }
+}
+
+/// Knobs that shape how `Generator` produces rows.
+#[derive(Debug, Clone, Default)]
+pub struct GenerateOptions {
+    /// Child rows copy city/state/country/coordinates from the parent they reference.
+    pub inherit_location: bool,
+    /// Buffer each table and drop columns that came out NULL in every row.
+    pub omit_null_columns: bool,
+    /// Fraction of rows (0.0-1.0) that get boundary values injected; 0 disables fuzzing.
+    pub fuzz_rate: f64,
+    pub format: OutputFormat,
+    /// NULL rate for nullable columns whose source NULL ratio wasn't measured.
+    pub null_probability: f64,
+    /// Give each nullable column one fill rate per table and leave its earliest rows NULL,
+    /// like a feature rolled out partway through the data, instead of scattering NULLs.
+    pub sparse_columns: bool,
+    pub section: Section,
+    pub fk_distribution: FkDistribution,
+    /// Rows per INSERT statement; 0 puts each table in a single statement.
+    pub batch_size: usize,
+    /// Mark each table with a savepoint inside the single dump transaction.
+    pub savepoints: bool,
+    /// Give each table its own transaction so a failing table doesn't undo the others.
+    pub continue_on_error: bool,
+    /// Write no BEGIN/SET CONSTRAINTS/COMMIT at all; the loader manages the transaction.
+    pub no_transaction: bool,
+    /// Empty every table (children first) before inserting, so the dump can be re-applied.
+    pub truncate: bool,
+    /// Pinned columns from `--overrides`, keyed by `table.column`, `schema.table.column` or `*.column`.
+    pub overrides: HashMap<String, ColumnOverride>,
+    /// Rows from `--fixtures`, keyed by `schema.table`; their values win over overrides and
+    /// generation, and `Plan::apply_fixtures` has already sized the tables to match.
+    pub fixtures: HashMap<String, FixtureRows>,
+    /// Make every INSERT idempotent against rows already present, keyed on the primary key.
+    pub on_conflict: Option<OnConflict>,
+    /// Draw IP address columns from routable public space unless named internal/private.
+    pub public_ips: bool,
+    /// Columns left out of every INSERT so their default or NULL applies, keyed like `overrides`.
+    pub excluded_columns: HashSet<String>,
+    /// Random bytes written into each `bytea` value.
+    pub bytea_length: usize,
+    /// Sequential primary keys start at `pk_offset + 1`, so separate runs get disjoint ID ranges.
+    pub pk_offset: u64,
+    /// Per-table replacements for `pk_offset`, keyed by `schema.table`. Unlike `pk_offset` they
+    /// also shift GENERATED ALWAYS keys, since the database continues those where the dump
+    /// being appended to left them.
+    pub pk_offsets: HashMap<String, u64>,
+    /// Add to the end of an existing dump file instead of replacing it.
+    pub append: bool,
+    /// Standard deviation, as a percentage of the base row count, of each table's size;
+    /// 0 gives every table exactly the base count. Explicit per-table counts are kept.
+    pub rows_stddev: f64,
+    /// Chance that a sequential primary key skips ahead of the previous one, mimicking deleted
+    /// rows; FKs still only reference keys that were emitted.
+    pub pk_gap_rate: f64,
+    /// Transliterate generated text to ASCII; otherwise names and text keep their accents.
+    pub ascii_only: bool,
+    /// Leave every nullable FK NULL instead of linking it, to exercise orphan handling.
+    pub null_optional_fks: bool,
+    /// Compress the SQL dump; `generate_sql_dump` appends the matching extension to the file name.
+    pub compress: Option<Compression>,
+    /// Stop emitting rows once the SQL dump reaches this many bytes (before compression). A table that wouldn't fit
+    /// whole is skipped with everything after it; only the first one is cut short instead, at the row that crosses
+    /// the limit. Either way the INSERT is closed and the transaction still commits.
+    pub max_size: Option<u64>,
+    /// Fail when a NOT NULL FK has no parent row to point at, instead of writing a placeholder.
+    pub strict_fk: bool,
+    /// Street and postal code formats, and the country places are drawn from.
+    pub locale: Option<Locale>,
+    /// Insert tables in the plan's order as given (`--tables`) instead of re-sorting them.
+    pub keep_order: bool,
+    /// Summarize every written column (NULLs, distinct values, numeric range) for `stats()`.
+    pub collect_stats: bool,
+    /// After the data, advance serial/identity key sequences past the largest generated key.
+    pub reset_sequences: bool,
+    /// Forest shape for self-referencing FKs; `None` attaches rows to random earlier ones.
+    pub tree: Option<TreeShape>,
+    /// Seed for every random draw of the run; `None` picks one at random (it is still
+    /// recorded in the manifest).
+    pub seed: Option<u64>,
+}
+
+/// A handle on the generator's seeded RNG. Each draw borrows the RNG only for its own
+/// duration, so handles can be held across calls that take one of their own.
+struct SharedRng<'a>(&'a RefCell<StdRng>);
+
+impl RngCore for SharedRng<'_> {
+    fn next_u32(&mut self) -> u32 {
+        self.0.borrow_mut().next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.borrow_mut().next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.0.borrow_mut().fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand::Error> {
+        self.0.borrow_mut().try_fill_bytes(dest)
+    }
+}
+
+/// The range column an EXCLUDE constraint is kept through, and where each group of rows
+/// sharing its `=` members had its last range end.
+type ExclusionCursor = (usize, HashMap<Vec<String>, i64>);
+
+/// What the row loop needs to know about a table's columns, worked out once per table
+/// rather than once per row.
+struct TableAnalysis {
+    /// Semantic type of each column, in column order.
+    semantics: Vec<SemanticType>,
+    /// Column indices in generation order: highest priority first, ties in column order.
+    order: Vec<usize>,
+    /// The column holding the row's primary key, if one is classified as such.
+    pk_index: Option<usize>,
+    /// `sparse_columns` only: the first row each sparse column gets a value in.
+    first_filled_rows: HashMap<String, usize>,
+}
+
+/// Range the per-table fill rate of a `sparse_columns` column is drawn from.
+const SPARSE_FILL_RATES: std::ops::Range<f64> = 0.2..0.9;
+
+/// Opens the `setval` statements that follow the data with `reset_sequences`.
+const SEQUENCE_RESETS_HEADER: &str = "\n-- Advance sequences past the generated keys\n";
+
+/// Share of unlinked children for a nullable ON DELETE SET NULL FK whose NULL ratio wasn't
+/// measured: the rows whose parent has since been deleted.
+const SET_NULL_ORPHAN_RATE: f64 = 0.1;
+
+pub struct Generator {
+    tables: Vec<Table>,
+    semantic_types: HashMap<String, BTreeMap<String, SemanticType>>,
+    options: GenerateOptions,
+    pk_storage: HashMap<String, Vec<String>>,
+    location_storage: HashMap<String, HashMap<String, Location>>,
+    email_locals: RefCell<HashMap<String, usize>>,
+    fk_cursors: RefCell<HashMap<String, Vec<usize>>>,
+    trees: RefCell<HashMap<String, TreeLevels>>,
+    progress: ProgressBar,
+    source_database: Option<String>,
+    providers: Vec<(ProviderMatch, Box<dyn Provider>)>,
+    row_hook: Option<RowHook>,
+    started_at: DateTime<Utc>,
+    rows_written: BTreeMap<String, usize>,
+    stats: GenerationStats,
+    /// `schema.table.column` of every ON DELETE CASCADE FK.
+    cascading_fks: HashSet<String>,
+    seed: u64,
+    rng: RefCell<StdRng>,
+}
+
+impl Generator {
+    pub fn new(mut plan: Plan, options: GenerateOptions) -> Self {
+        plan.tables.sort_by_key(|t| t.sort_order);
+        let semantic_types = plan.tables.iter()
+            .map(|t| (t.table.qualified_name(), t.semantic_types.clone()))
+            .collect();
+        let cascading_fks = plan.tables.iter()
+            .flat_map(|t| t.table.foreign_keys.iter().filter(|fk| fk.cascades()).map(|fk| format!("{}.{}", t.table.qualified_name(), fk.column)))
+            .collect();
+        let seed = options.seed.unwrap_or_else(|| rand::thread_rng().gen());
+        Self { 
+            tables: plan.tables.into_iter().map(|t| t.table).collect(),
+            semantic_types,
+            options,
+            pk_storage: HashMap::new(),
+            location_storage: HashMap::new(),
+            email_locals: RefCell::new(HashMap::new()),
+            fk_cursors: RefCell::new(HashMap::new()),
+            trees: RefCell::new(HashMap::new()),
+            progress: ProgressBar::hidden(),
+            source_database: plan.source_database,
+            providers: Vec::new(),
+            row_hook: None,
+            started_at: Utc::now(),
+            rows_written: BTreeMap::new(),
+            stats: GenerationStats::default(),
+            cascading_fks,
+            seed,
+            rng: RefCell::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+    
+    /// The run's RNG; every random draw goes through it so `--seed` can repeat a run.
+    fn rng(&self) -> SharedRng<'_> {
+        SharedRng(&self.rng)
+    }
+    
+    /// Routes matching columns to `provider` instead of the built-in generators. Providers
+    /// are tried in registration order; column overrides still win over them.
+    pub fn register_provider(&mut self, matcher: ProviderMatch, provider: impl Provider + 'static) {
+        self.providers.push((matcher, Box::new(provider)));
+    }
+    
+    /// Lets the caller inspect or rewrite each row before it is written, e.g. to enforce a
+    /// business rule across columns. Values are SQL literals (quote text with
+    /// [`crate::sql::escape_sql_string`]). UNIQUE keys are re-checked after the hook; a
+    /// rewritten primary key is what child rows reference. Replaces any earlier hook.
+    pub fn set_row_hook(&mut self, hook: impl Fn(&Table, &mut Vec<String>, usize) + 'static) {
+        self.row_hook = Some(Box::new(hook));
+    }
+    
+    /// Provenance of the rows generated so far; call after `generate_sql_dump`.
+    pub fn manifest(&self) -> Manifest {
+        let format = match self.options.format {
+            OutputFormat::Sql => "sql",
+            OutputFormat::Csv => "csv",
+        };
+        Manifest {
+            synthdb_version: env!("CARGO_PKG_VERSION").to_string(),
+            generated_at: self.started_at.to_rfc3339(),
+            dialect: "postgresql".to_string(),
+            format: format.to_string(),
+            seed: Some(self.seed),
+            source_database: self.source_database.clone(),
+            tables: self.rows_written.clone(),
+            last_keys: self.pk_storage.iter()
+                .filter_map(|(table, ids)| Some((table.clone(), ids.iter().filter_map(|id| id.parse().ok()).max()?)))
+                .collect(),
+        }
+    }
+
+    /// Per-column summary of the rows generated so far; empty unless `collect_stats` is set.
+    pub fn stats(&self) -> &GenerationStats {
+        &self.stats
+    }
+
+    /// Writes the dump to `output_file`, or to stdout when it is `-`.
+    pub fn generate_sql_dump(&mut self, output_file: &str, row_count: usize) -> Result<()> {
+        let to_stdout = output_file == "-";
+        if self.options.format == OutputFormat::Csv {
+            if self.options.section != Section::Data {
+                bail!("--section schema/all is only available with --format sql");
+            }
+            if to_stdout {
+                bail!("--output - is only available with --format sql; CSV needs a directory");
+            }
+            return self.generate_csv(output_file, row_count);
+        }
+        
+        let path = dump_path(output_file, self.options.compress);
+        let inner: Box<dyn Write> = if to_stdout {
+            Box::new(io::stdout().lock())
+        } else if self.options.append {
+            // gzip and zstd both decode concatenated streams, so a compressed dump can grow too.
+            Box::new(File::options().append(true).open(&path).with_context(|| format!("cannot append to '{}'", path))?)
+        } else {
+            Box::new(File::create(&path).with_context(|| format!("cannot create '{}'", path))?)
+        };
+        let mut writer = BufWriter::new(DumpSink::new(inner, self.options.compress)?);
+        self.write_sql_dump(&mut writer, row_count)?;
+        writer.into_inner().map_err(|e| e.into_error())?.finish()?;
+        
+        let destination = if to_stdout { "stdout" } else { path.as_str() };
+        if self.options.section == Section::Schema {
+            status!("\n✅ Schema DDL: {}", destination);
+        } else {
+            status!("\n✅ AI-Generated SQL: {}", destination);
+            self.print_summary();
+        }
+        Ok(())
+    }
+    
+    /// Writes the SQL dump (header, optional DDL, data) to any writer; CSV output needs
+    /// a directory and goes through `generate_sql_dump` instead.
+    pub fn write_sql_dump(&mut self, writer: &mut impl Write, row_count: usize) -> Result<()> {
+        let writer = &mut CountingWriter { inner: writer, written: 0 };
+        writeln!(writer, "-- SynthDB Deep Learning AI Generator v{}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(writer, "-- Generated: {} (UTC)", self.started_at.format("%Y-%m-%d %H:%M:%S"))?;
+        if self.options.rows_stddev > 0.0 {
+            writeln!(writer, "-- Rows per table: {} (stddev {}%)", row_count, self.options.rows_stddev)?;
+        } else {
+            writeln!(writer, "-- Rows per table: {}", row_count)?;
+        }
+        writeln!(writer, "-- AI: Deep Semantic Analysis, Pattern Recognition, Context Learning")?;
+
+        let mut sorted_tables = self.sorted_tables();
+        self.jitter_row_counts(&mut sorted_tables, row_count);
+
+        // Checked before anything is written, so a refused run leaves no half-written dump.
+        if self.options.section != Section::Schema {
+            self.check_required_parents(&sorted_tables, row_count)?;
+            self.check_deferred_fks(&sorted_tables)?;
+        }
+        if self.options.section != Section::Data {
+            ddl::write_schema(writer, &sorted_tables)?;
+        }
+        if self.options.section == Section::Schema {
+            return Ok(());
+        }
+
+        // Only DEFERRABLE constraints can wait for COMMIT; the rest are checked row by row,
+        // which the parents-first order already satisfies.
+        let defer = sorted_tables.iter().flat_map(|t| &t.foreign_keys).any(|fk| fk.deferrable);
+        let framed = !self.options.continue_on_error && !self.options.no_transaction;
+        if framed {
+            writeln!(writer, "BEGIN;")?;
+            if defer {
+                writeln!(writer, "SET CONSTRAINTS ALL DEFERRED;")?;
+            }
+            writeln!(writer)?;
+        }
+
+        if self.options.truncate {
+            // Children first, so each TRUNCATE finds nothing left referencing it.
+            writeln!(writer, "-- Clear existing data")?;
+            for table in sorted_tables.iter().rev().filter(|t| !t.is_view) {
+                writeln!(writer, "TRUNCATE {} RESTART IDENTITY CASCADE;", quote_table(&table.schema_name, &table.table_name))?;
+            }
+            writeln!(writer)?;
+        }
+
+        let batch_size = if self.options.batch_size == 0 { usize::MAX } else { self.options.batch_size };
+
+        let max_size = self.options.max_size.unwrap_or(u64::MAX);
+        // Bytes already promised to what follows the rows: the final COMMIT and sequence resets,
+        // the current table's closing lines, and the back-patch UPDATEs of the rows written.
+        let mut reserved = self.trailer_size(&sorted_tables, framed);
+        let mut emitted_tables = 0;
+        let mut wrote_rows = false;
+        // Where --max-size ended the data: the table it stopped in (or before), and its rows written.
+        let mut stopped: Option<(&Table, usize)> = None;
+        self.start_progress(&sorted_tables, row_count);
+        for table in &sorted_tables {
+            if writer.written + reserved >= max_size {
+                stopped = Some((table, 0));
+                break;
+            }
+            let table_rows = table.row_count.unwrap_or(row_count);
+            if table_rows == 0 {
+                // No INSERT (it would have an empty VALUES list), savepoint or transaction; the
+                // table is still registered so its children see it has no parents.
+                writeln!(writer, "-- No rows for {}\n", comment_text(&table.table_name))?;
+                self.generate_table(table, 0, |_, _| Ok(ControlFlow::Continue(())))?;
+                emitted_tables += 1;
+                continue;
+            }
+            self.progress.suspend(|| status!("📊 Generating semantic data for: {}", table.table_name));
+            
+            let mut included = self.insertable_columns(table);
+
+            // Dropping all-NULL columns needs every row seen before the INSERT is written.
+            let buffered = if self.options.omit_null_columns {
+                let mut buffered = Vec::new();
+                self.generate_table(table, table_rows, |_, row_values| {
+                    buffered.push(row_values);
+                    Ok(ControlFlow::Continue(()))
+                })?;
+                let non_null: Vec<usize> = included.iter().copied()
+                    .filter(|&idx| buffered.iter().any(|row: &Vec<String>| row[idx] != "NULL"))
+                    .collect();
+                // Dropping every column would leave nothing to insert; keep the full list then.
+                if !non_null.is_empty() && non_null.len() < included.len() {
+                    let dropped: Vec<&str> = included.iter()
+                        .filter(|idx| !non_null.contains(idx))
+                        .map(|&idx| table.columns[idx].name.as_str())
+                        .collect();
+                    self.progress.suspend(|| {
+                        status!("   ⚠️ Omitting all-NULL columns from {}: {}", table.table_name, dropped.join(", "))
+                    });
+                    included = non_null;
+                }
+                Some(buffered)
+            } else {
+                None
+            };
+            let target = self.insert_target(table, included);
+            let footprint = self.table_footprint(&target, defer);
+            let whole_table = buffered.as_ref().map(|rows| {
+                rows.iter().enumerate().map(|(row_idx, row)| footprint.row_size(&target, row, row_idx, batch_size)).sum::<u64>()
+            });
+
+            let mut written_rows = 0;
+            let mut emit = |row_idx: usize, row_values: &[String]| -> Result<ControlFlow<()>> {
+                let size = footprint.row_size(&target, row_values, row_idx, batch_size);
+                if row_idx == 0 {
+                    // --max-size keeps to whole tables, judged from the exact size when the rows are
+                    // buffered and from the first row otherwise; only a first table too big on its
+                    // own is cut short.
+                    let rows = whole_table.unwrap_or(size * table_rows as u64);
+                    if wrote_rows && writer.written + reserved + footprint.fixed_size() + rows > max_size {
+                        return Ok(ControlFlow::Break(()));
+                    }
+                    writer.write_all(footprint.opening.as_bytes())?;
+                    reserved += footprint.closing.len() as u64 + footprint.trailer;
+                }
+                // Stop while another row of about this size would still fit, so the dump stays under the cap.
+                let last = writer.written + reserved + 2 * size >= max_size;
+                let total_rows = if last { row_idx + 1 } else { table_rows };
+                Self::write_batched_row(writer, &target, row_values, row_idx, total_rows, batch_size)?;
+                reserved += footprint.backpatch_size(row_values);
+                written_rows = row_idx + 1;
+                Ok(if last { ControlFlow::Break(()) } else { ControlFlow::Continue(()) })
+            };
+            match &buffered {
+                Some(buffered) => {
+                    for (row_idx, row_values) in buffered.iter().enumerate() {
+                        if emit(row_idx, row_values)?.is_break() {
+                            break;
+                        }
+                    }
+                },
+                None => self.generate_table(table, table_rows, |row_idx, row_values| emit(row_idx, &row_values))?,
+            }
+            if written_rows < table_rows {
+                self.forget_rows_after(table, written_rows);
+            }
+            if written_rows == 0 {
+                stopped = Some((table, 0));
+                break;
+            }
+            emitted_tables += 1;
+            wrote_rows = true;
+
+            writer.write_all(footprint.closing.as_bytes())?;
+            reserved -= footprint.closing.len() as u64;
+            if written_rows < table_rows {
+                stopped = Some((table, written_rows));
+                break;
+            }
+        }
+        
+        if let Some((table, rows)) = stopped {
+            let skipped = sorted_tables.len() - emitted_tables;
+            if rows > 0 {
+                self.progress.suspend(|| status!(
+                    "⚠️ --max-size reached: stopped after {} row(s) of {}; {} table(s) skipped",
+                    rows, table.table_name, skipped
+                ));
+            } else {
+                self.progress.suspend(|| status!(
+                    "⚠️ --max-size reached: stopped before {}; {} table(s) skipped",
+                    table.table_name, skipped
+                ));
+            }
+        }
+        
+        self.write_backpatch(writer, &sorted_tables[..emitted_tables])?;
+        
+        if framed {
+            writeln!(writer, "COMMIT;")?;
+        }
+        if self.options.reset_sequences {
+            self.write_sequence_resets(writer, &sorted_tables[..emitted_tables])?;
+        }
+        self.progress.finish_and_clear();
+        Ok(())
+    }
+    
+    /// Moves the sequence behind each serial or identity primary key past the largest key
+    /// written, so the application's next INSERT doesn't collide with a synthetic row.
+    /// GENERATED ALWAYS keys were numbered by the sequence itself and need nothing.
+    fn write_sequence_resets(&self, writer: &mut impl Write, tables: &[Table]) -> Result<()> {
+        let resets = self.sequence_resets(tables.iter()
+            .filter(|t| self.rows_written.get(&t.qualified_name()).is_some_and(|&n| n > 0)));
+        if resets.is_empty() {
+            return Ok(());
+        }
+        write!(writer, "{}", SEQUENCE_RESETS_HEADER)?;
+        for reset in resets {
+            writeln!(writer, "{}", reset)?;
+        }
+        Ok(())
+    }
+    
+    /// The `setval` statement for each of `tables` with a sequence-backed primary key.
+    fn sequence_resets<'t>(&self, tables: impl Iterator<Item = &'t Table>) -> Vec<String> {
+        let keys: Vec<(&Table, &Column)> = tables
+            .filter(|t| t.primary_keys.len() == 1)
+            .filter_map(|t| Some((t, t.columns.iter().find(|c| c.name == t.primary_keys[0])?)))
+            .filter(|(t, c)| !c.is_generated && !self.is_excluded(t, c))
+            .filter(|(_, c)| c.identity_generation.is_some() || c.column_default.as_deref().is_some_and(|d| d.starts_with("nextval(")))
+            .collect();
+        // pg_get_serial_sequence parses the table name itself and knows no U&"..." escapes, so
+        // it gets the plainly quoted form inside the (E'...' escaped) string.
+        let plain = |name: &str| format!("\"{}\"", name.replace('"', "\"\""));
+        keys.into_iter().map(|(table, col)| {
+            let name = quote_table(&table.schema_name, &table.table_name);
+            let text = if table.schema_name == "public" {
+                plain(&table.table_name)
+            } else {
+                format!("{}.{}", plain(&table.schema_name), plain(&table.table_name))
+            };
+            format!("SELECT setval(pg_get_serial_sequence({}, {}), max({})) FROM {};",
+                escape_sql_string(&text), escape_sql_string(&col.name), quote_ident(&col.name), name)
+        }).collect()
+    }
+    
+    /// What the dump writes after the last table whichever tables make it in: the closing
+    /// COMMIT, and the heading of the sequence resets if any table may need one.
+    fn trailer_size(&self, tables: &[Table], framed: bool) -> u64 {
+        let commit = if framed { "COMMIT;\n".len() } else { 0 };
+        let resets = self.options.reset_sequences && !self.sequence_resets(tables.iter()).is_empty();
+        let heading = if resets { SEQUENCE_RESETS_HEADER.len() } else { 0 };
+        (commit + heading) as u64
+    }
+    
+    /// The lines around `target`'s rows and the back-patch bytes they bring, for `--max-size`.
+    fn table_footprint(&self, target: &InsertTarget, defer: bool) -> TableFootprint {
+        let table = target.table;
+        let mut opening = format!("-- Data for {}\n", comment_text(&table.table_name));
+        let mut closing = String::new();
+        let savepoint = quote_ident(&format!("t_{}", table.table_name));
+        if self.options.continue_on_error {
+            opening.push_str("BEGIN;\n");
+            if defer {
+                opening.push_str("SET CONSTRAINTS ALL DEFERRED;\n");
+            }
+            closing.push_str("COMMIT;\n");
+        } else if self.options.savepoints {
+            opening.push_str(&format!("SAVEPOINT {};\n", savepoint));
+            closing.push_str(&format!("RELEASE SAVEPOINT {};\n", savepoint));
+        }
+        closing.push('\n');
+
+        let col_names: Vec<String> = target.included.iter().map(|&idx| quote_ident(&table.columns[idx].name)).collect();
+        let insert_header = format!("INSERT INTO {} ({}) VALUES\n", quote_table(&table.schema_name, &table.table_name), col_names.join(", "));
+
+        // Each back-patch UPDATE is costed with the row's own key standing in for the parent's.
+        let pk_index = table.primary_keys.first().and_then(|pk| table.columns.iter().position(|c| &c.name == pk));
+        let deferred: Vec<&str> = table.foreign_keys.iter()
+            .filter(|fk| fk.deferred && pk_index.is_some())
+            .map(|fk| fk.column.as_str())
+            .collect();
+        let transaction = if self.options.continue_on_error { "BEGIN;\nCOMMIT;\n".len() } else { 0 };
+        let backpatch = deferred.iter()
+            .map(|col| format!("-- Back-patch deferred FK {}.{}\n\n", comment_text(&table.table_name), comment_text(col)).len() + transaction)
+            .sum::<usize>();
+        let reset = if self.options.reset_sequences {
+            self.sequence_resets(std::iter::once(table)).iter().map(|line| line.len() + 1).sum()
+        } else {
+            0
+        };
+        let backpatch_row = pk_index.map_or(0, |idx| deferred.iter()
+            .map(|col| format!("UPDATE {} SET {} =  WHERE {} = ;\n",
+                quote_table(&table.schema_name, &table.table_name), quote_ident(col), quote_ident(&table.columns[idx].name)).len())
+            .sum::<usize>());
+
+        TableFootprint {
+            opening,
+            closing,
+            insert_header: insert_header.len() as u64,
+            trailer: (backpatch + reset) as u64,
+            backpatch_row: backpatch_row as u64,
+            backpatch_fks: deferred.len() as u64,
+            pk_index,
+        }
+    }
+    
+    /// Drops the keys of buffered rows that were generated but never written, so deferred FKs
+    /// aren't back-patched to point at them.
+    fn forget_rows_after(&mut self, table: &Table, written_rows: usize) {
+        let table_key = table.qualified_name();
+        if let Some(pks) = self.pk_storage.get_mut(&table_key) {
+            pks.truncate(written_rows);
+        }
+        self.rows_written.insert(table_key, written_rows);
+    }
+    
+    /// Writes one RFC 4180 CSV file per table into `output_dir`, with a header row and
+    /// NULL as an empty unquoted field.
+    fn generate_csv(&mut self, output_dir: &str, row_count: usize) -> Result<()> {
+        fs::create_dir_all(output_dir)?;
+        
+        let mut sorted_tables = self.sorted_tables();
+        self.jitter_row_counts(&mut sorted_tables, row_count);
+        self.check_required_parents(&sorted_tables, row_count)?;
+        if sorted_tables.iter().any(|t| t.foreign_keys.iter().any(|fk| fk.deferred)) {
+            status!("⚠️ CSV output can't back-patch cyclic FKs; deferred columns keep placeholder values");
+        }
+        self.start_progress(&sorted_tables, row_count);
+        for table in sorted_tables {
+            self.progress.suspend(|| status!("📊 Generating semantic data for: {}", table.table_name));
+            
+            let file_name = if table.schema_name == "public" {
+                format!("{}.csv", table.table_name)
+            } else {
+                format!("{}.{}.csv", table.schema_name, table.table_name)
+            };
+            let mut writer = BufWriter::new(File::create(Path::new(output_dir).join(file_name))?);
+            
+            let included = self.insertable_columns(&table);
+            let header: Vec<String> = included.iter().map(|&idx| csv_field(Some(&table.columns[idx].name))).collect();
+            write!(writer, "{}\r\n", header.join(","))?;
+            
+            let table_rows = table.row_count.unwrap_or(row_count);
+            self.generate_table(&table, table_rows, |_, row_values| {
+                let fields: Vec<String> = included.iter()
+                    .map(|&idx| csv_field(sql_literal_to_raw(&row_values[idx]).as_deref()))
+                    .collect();
+                write!(writer, "{}\r\n", fields.join(","))?;
+                Ok(ControlFlow::Continue(()))
+            })?;
+            writer.flush()?;
+        }
+        
+        self.progress.finish_and_clear();
+        status!("\n✅ AI-Generated CSV files in: {}", output_dir);
+        self.print_summary();
+        
+        Ok(())
+    }
+    
+    fn sorted_tables(&self) -> Vec<Table> {
+        let sorted_tables = if self.options.keep_order {
+            self.tables.clone()
+        } else {
+            TopologicalSorter::sort(&self.tables)
+        };
+        
+        status!("🧠 Deep Learning Analysis Complete:");
+        for (idx, table) in sorted_tables.iter().enumerate() {
+            status!("   {}. {} ({} columns analyzed)", idx + 1, table.table_name, table.columns.len());
+        }
+        status!();
+        
+        sorted_tables
+    }
+    
+    /// Gives every table without an explicit count its own size, drawn from a normal
+    /// distribution around `row_count` with `rows_stddev` percent spread. Sizes stay at least
+    /// 1 so no parent ends up empty, and come from the run's RNG so `--seed` repeats them.
+    fn jitter_row_counts(&self, tables: &mut [Table], row_count: usize) {
+        if self.options.rows_stddev <= 0.0 || row_count == 0 {
+            return;
+        }
+        let Ok(normal) = Normal::new(row_count as f64, row_count as f64 * self.options.rows_stddev / 100.0) else { return };
+        let mut rng = self.rng();
+        for table in tables.iter_mut().filter(|t| t.row_count.is_none()) {
+            table.row_count = Some(normal.sample(&mut rng).round().max(1.0) as usize);
+        }
+    }
+    
+    /// Fails before any row is written if a NOT NULL FK column would have no parent row to
+    /// point at: the referenced table is getting zero rows or isn't being generated at all.
+    fn check_required_parents(&self, tables: &[Table], row_count: usize) -> Result<()> {
+        let planned_rows: HashMap<String, usize> = tables.iter()
+            .map(|t| (t.qualified_name(), t.row_count.unwrap_or(row_count)))
+            .collect();
+        let mut problems = Vec::new();
+        for table in tables.iter().filter(|t| planned_rows[&t.qualified_name()] > 0) {
+            for fk in &table.foreign_keys {
+                let parent = fk.ref_qualified_name();
+                // A self-reference is satisfied by the table's own rows; an override supplies its own value.
+                if parent == table.qualified_name() {
+                    continue;
+                }
+                let Some(col) = table.columns.iter().find(|c| c.name == fk.column) else { continue };
+                if col.is_nullable || self.has_override(table, col, 0) {
+                    continue;
+                }
+                match planned_rows.get(&parent) {
+                    Some(0) => problems.push(format!(
+                        "{}.{} is NOT NULL but its parent {} is set to generate 0 rows", table.qualified_name(), col.name, parent)),
+                    None => problems.push(format!(
+                        "{}.{} is NOT NULL but its parent {} is not among the generated tables", table.qualified_name(), col.name, parent)),
+                    Some(_) => {}
+                }
+            }
+        }
+        if !problems.is_empty() {
+            bail!("foreign keys with no parent rows to reference:\n  {}", problems.join("\n  "));
+        }
+        Ok(())
+    }
+    
+    /// Fails when a NOT NULL deferred FK would be checked against its placeholder before
+    /// `write_backpatch` fixes it. Under `--continue-on-error` every table commits on its own,
+    /// so even a DEFERRABLE constraint is checked at that table's COMMIT; under
+    /// `--no-transaction` there is no COMMIT to defer to and each INSERT is checked at once.
+    /// `--max-size` may stop before the parent is written, leaving nothing to back-patch with.
+    fn check_deferred_fks(&self, tables: &[Table]) -> Result<()> {
+        let placeholders: Vec<String> = tables.iter()
+            .flat_map(|t| t.foreign_keys.iter()
+                .filter(|fk| fk.deferred && !fk.inferred)
+                .filter(|fk| t.columns.iter().any(|c| c.name == fk.column && !c.is_nullable))
+                .map(move |fk| format!("{}.{}", t.qualified_name(), fk.column)))
+            .collect();
+        if placeholders.is_empty() {
+            return Ok(());
+        }
+        if self.options.continue_on_error {
+            bail!("--continue-on-error commits each table before the back-patch UPDATEs run, so these NOT NULL \
+                cycle-breaking FKs would be checked against placeholders: {}", placeholders.join(", "));
+        }
+        if self.options.no_transaction {
+            bail!("--no-transaction checks every INSERT on its own, so these NOT NULL cycle-breaking FKs would be \
+                checked against placeholders before the back-patch UPDATEs run: {}", placeholders.join(", "));
+        }
+        if self.options.max_size.is_some() {
+            bail!("--max-size may stop before the parent tables of these NOT NULL cycle-breaking FKs are written, \
+                leaving their placeholders nothing to be back-patched with: {}", placeholders.join(", "));
+        }
+        Ok(())
+    }
+    
+    fn start_progress(&mut self, tables: &[Table], row_count: usize) {
+        let total: usize = tables.iter().map(|t| t.row_count.unwrap_or(row_count)).sum();
+        self.progress = ui::progress_bar(total as u64);
+    }
+    
+    /// Points deferred (cycle-breaking) FKs at real parent rows now that every table exists,
+    /// one UPDATE per child row keyed by its primary key.
+    fn write_backpatch(&self, writer: &mut impl Write, tables: &[Table]) -> Result<()> {
+        let mut rng = self.rng();
+        for table in tables {
+            for fk in table.foreign_keys.iter().filter(|fk| fk.deferred) {
+                let Some(col) = table.columns.iter().find(|c| c.name == fk.column) else { continue };
+                let pk_col = table.primary_keys.first()
+                    .and_then(|name| table.columns.iter().find(|c| &c.name == name));
+                let (Some(pk_col), Some(pks)) = (pk_col, self.pk_storage.get(&table.qualified_name())) else {
+                    status!("⚠️ Can't back-patch {}.{}: the table has no primary key", table.table_name, fk.column);
+                    continue;
+                };
+
+                if self.options.continue_on_error {
+                    writeln!(writer, "BEGIN;")?;
+                }
+                writeln!(writer, "-- Back-patch deferred FK {}.{}", comment_text(&table.table_name), comment_text(&fk.column))?;
+                let null_rate = col.observed_null_ratio.unwrap_or(self.options.null_probability);
+                for pk in pks {
+                    if col.is_nullable && null_rate > 0.0 && rng.gen_bool(null_rate.min(1.0)) {
+                        continue;
+                    }
+                    let value = self.get_fk_value(&fk.ref_qualified_name(), col, &table.qualified_name());
+                    if value == "NULL" && self.options.strict_fk && !col.is_nullable {
+                        bail!("--strict-fk: {}.{} is NOT NULL but {} has no rows to back-patch it with",
+                            table.qualified_name(), fk.column, fk.ref_qualified_name());
+                    }
+                    if value == "NULL" {
+                        continue;
+                    }
+                    writeln!(writer, "UPDATE {} SET {} = {} WHERE {} = {};",
+                        quote_table(&table.schema_name, &table.table_name),
+                        quote_ident(&fk.column),
+                        value,
+                        quote_ident(&pk_col.name),
+                        Self::key_literal(pk, &pk_col.data_type)
+                    )?;
+                }
+                if self.options.continue_on_error {
+                    writeln!(writer, "COMMIT;")?;
+                }
+                writeln!(writer)?;
+            }
+        }
+        Ok(())
+    }
+    
+    fn print_summary(&self) {
+        status!("📊 Tables: {} | Total rows: {}", 
+            self.pk_storage.len(), 
+            self.pk_storage.values().map(|v| v.len()).sum::<usize>()
+        );
+    }
+    
+    /// Generates `table_rows` rows for `table`, recording PKs (and locations) for later
+    /// FK lookups, and hands each row to `emit` as SQL literals. `emit` can end the table
+    /// early by breaking; the rows before (and including) that one are kept.
+    fn generate_table(
+        &mut self,
+        table: &Table,
+        table_rows: usize,
+        mut emit: impl FnMut(usize, Vec<String>) -> Result<ControlFlow<()>>,
+    ) -> Result<()> {
+        // PKs are recorded as rows are produced so self-referencing FKs can point back at them.
+        let table_key = table.qualified_name();
+        self.pk_storage.insert(table_key.clone(), Vec::new());
+        let mut table_locations = HashMap::new();
+        let mut unique_seen = vec![HashSet::new(); table.unique_keys.len()];
+        let mut exclusion_cursors = self.exclusion_cursors(table);
+        let mut table_stats = self.options.collect_stats.then(|| TableStats {
+            rows: 0,
+            columns: table.columns.iter()
+                .enumerate()
+                .filter(|(_, col)| !col.is_generated && !self.is_excluded(table, col))
+                .map(|(index, col)| ColumnStats {
+                    column: col.name.clone(),
+                    semantic_type: self.semantic_type(col, table),
+                    nulls: 0,
+                    distinct: 0,
+                    min: None,
+                    max: None,
+                    index,
+                    seen: HashSet::new(),
+                })
+                .collect(),
+        });
+
+        let analysis = self.analyze_table(table, table_rows);
+
+        self.progress.set_message(table.table_name.clone());
+        let mut generated = 0;
+        for row_idx in 0..table_rows {
+            let mut row_values = self.generate_intelligent_row(table, &analysis, row_idx)?;
+            self.enforce_unique_keys(table, &mut row_values, &mut unique_seen, row_idx);
+            self.enforce_exclusions(table, &mut row_values, &mut exclusion_cursors);
+            self.progress.inc(1);
+
+            let row_pk = analysis.pk_index.and_then(|idx| sql_literal_to_raw(&row_values[idx]));
+
+            if self.options.inherit_location {
+                let mut location = Location::default();
+                for (idx, semantic) in analysis.semantics.iter().enumerate() {
+                    if let Some(slot) = location.slot_mut(semantic) {
+                        *slot = Some(row_values[idx].clone());
+                    }
+                }
+                if let (false, Some(pk)) = (location.is_empty(), &row_pk) {
+                    table_locations.insert(pk.clone(), location);
+                }
+            }
+
+            if let Some(pk) = row_pk {
+                self.pk_storage.entry(table_key.clone()).or_default().push(pk);
+            }
+
+            if let Some(stats) = table_stats.as_mut() {
+                stats.record(&row_values);
+            }
+
+            generated += 1;
+            if emit(row_idx, row_values)?.is_break() {
+                break;
+            }
+        }
+
+        self.rows_written.insert(table_key.clone(), generated);
+        if let Some(stats) = table_stats {
+            self.stats.tables.insert(table_key.clone(), stats);
+        }
+        if !table_locations.is_empty() {
+            self.location_storage.insert(table_key, table_locations);
+        }
+        Ok(())
+    }
+    
+    /// Redraws members of any UNIQUE key whose values were already used earlier in the table,
+    /// so composite keys like `(org_id, slug)` hold at high row counts. The key's own columns
+    /// are varied first and its FK members only once that keeps failing; if the value pool is
+    /// exhausted, a text member gets the row number appended. Tuples containing NULL never
+    /// conflict in Postgres and aren't tracked.
+    fn enforce_unique_keys(&self, table: &Table, row_values: &mut [String], seen: &mut [HashSet<Vec<String>>], row_idx: usize) {
+        const MAX_ATTEMPTS: usize = 50;
+        for (key, used) in table.unique_keys.iter().zip(seen.iter_mut()) {
+            let redrawable: Vec<(usize, SemanticType)> = key.iter()
+                .filter_map(|name| table.columns.iter().position(|c| &c.name == name))
+                .map(|idx| (idx, self.semantic_type(&table.columns[idx], table)))
+                .filter(|(idx, semantic)| {
+                    let col = &table.columns[*idx];
+                    *semantic != SemanticType::PrimaryKey
+                        && !table.foreign_keys.iter().any(|fk| fk.deferred && fk.column == col.name)
+                        && !self.has_override(table, col, row_idx)
+                })
+                .collect();
+            let tuple = |row: &[String]| -> Vec<String> {
+                key.iter()
+                    .filter_map(|name| table.columns.iter().position(|c| &c.name == name))
+                    .map(|idx| row[idx].clone())
+                    .collect()
+            };
+
+            let mut attempt = 0;
+            while attempt < MAX_ATTEMPTS && used.contains(&tuple(row_values)) {
+                for (idx, semantic) in &redrawable {
+                    if matches!(semantic, SemanticType::ForeignKey(_)) && attempt < MAX_ATTEMPTS / 2 {
+                        continue;
+                    }
+                    let context = ContextEngine::new(&table.qualified_name());
+                    row_values[*idx] = self.generate_by_semantic(semantic, &table.columns[*idx], &context, row_idx);
+                }
+                attempt += 1;
+            }
+
+            if used.contains(&tuple(row_values)) {
+                let text_member = redrawable.iter().map(|(idx, _)| *idx).find(|&idx| {
+                    let dtype = &table.columns[idx].data_type;
+                    dtype.contains("char") || dtype.contains("text")
+                });
+                let raw = text_member.and_then(|idx| Some((idx, sql_literal_to_raw(&row_values[idx])?)));
+                if let Some((idx, raw)) = raw {
+                    let suffix = format!("-{}", row_idx + 1);
+                    let max_len = table.columns[idx].max_length.map(|n| n as usize).unwrap_or(usize::MAX);
+                    let base: String = raw.chars().take(max_len.saturating_sub(suffix.len())).collect();
+                    row_values[idx] = escape_sql_string(&format!("{}{}", base, suffix));
+                }
+            }
+
+            let values = tuple(row_values);
+            if values.iter().any(|v| v == "NULL") {
+                continue;
+            }
+            if !used.insert(values) {
+                self.progress.suspend(|| {
+                    status!("   ⚠️ No unused ({}) left for {} row {}; it will violate the unique constraint",
+                        key.join(", "), table.table_name, row_idx + 1)
+                });
+            }
+        }
+    }
+    
+    /// Keeps EXCLUDE constraints such as `(room_id WITH =, during WITH &&)`: rows sharing the
+    /// `=` columns get back-to-back values in one `&&` range column, each starting after the
+    /// group's last one ended. Two rows then never conflict, whatever the other members hold.
+    /// Rows with a NULL member never conflict and keep what they had.
+    fn enforce_exclusions(&self, table: &Table, row_values: &mut [String], cursors: &mut [Option<ExclusionCursor>]) {
+        let mut rng = self.rng();
+        for (constraint, cursor) in table.exclusion_constraints.iter().zip(cursors.iter_mut()) {
+            let Some((range_idx, ends)) = cursor.as_mut() else { continue };
+            let range_idx = *range_idx;
+            let Some(kind) = RangeKind::of(&table.columns[range_idx].data_type) else { continue };
+            let group: Vec<String> = constraint.iter()
+                .filter(|(_, op)| op == "=")
+                .filter_map(|(name, _)| table.columns.iter().position(|c| &c.name == name))
+                .map(|idx| row_values[idx].clone())
+                .collect();
+            if row_values[range_idx] == "NULL" || group.iter().any(|v| v == "NULL") {
+                continue;
+            }
+            let (gap, length) = kind.span(&mut rng);
+            let end = ends.entry(group).or_insert(kind.window().start);
+            let start = *end + gap;
+            *end = start + length;
+            row_values[range_idx] = escape_sql_string(&kind.literal(self.started_at, start, *end));
+        }
+    }
+    
+    /// Per EXCLUDE constraint, the range column `enforce_exclusions` spaces out and its
+    /// group -> last range end map, or `None` (with a warning) when no `&&` member is a
+    /// built-in range column free of overrides.
+    fn exclusion_cursors(&self, table: &Table) -> Vec<Option<ExclusionCursor>> {
+        table.exclusion_constraints.iter()
+            .map(|constraint| {
+                let range_idx = constraint.iter()
+                    .filter(|(_, op)| op == "&&")
+                    .filter_map(|(name, _)| table.columns.iter().position(|c| &c.name == name))
+                    .find(|&idx| {
+                        let col = &table.columns[idx];
+                        RangeKind::of(&col.data_type).is_some() && !self.has_override(table, col, 0)
+                    });
+                if range_idx.is_none() {
+                    let elements: Vec<String> = constraint.iter().map(|(name, op)| format!("{} WITH {}", name, op)).collect();
+                    self.progress.suspend(|| {
+                        status!("   ⚠️ Can't keep EXCLUDE ({}) on {}; rows may violate it", elements.join(", "), table.table_name)
+                    });
+                }
+                range_idx.map(|idx| (idx, HashMap::new()))
+            })
+            .collect()
+    }
+    
+    /// Indices of the columns that can appear in an INSERT; generated and `--exclude-column`
+    /// columns are still produced internally (identity PKs feed FK lookups) but never written.
+    fn insertable_columns(&self, table: &Table) -> Vec<usize> {
+        for col in table.columns.iter().filter(|c| self.is_excluded(table, c)) {
+            if !col.is_nullable && col.column_default.is_none() && !col.is_generated {
+                self.progress.suspend(|| {
+                    status!("   ⚠️ Excluded column {}.{} is NOT NULL with no default; the import will likely fail", table.table_name, col.name)
+                });
+            }
+        }
+        table.columns.iter()
+            .enumerate()
+            .filter(|(_, c)| !c.is_generated && !self.is_excluded(table, c))
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+    
+    /// Whether `--exclude-column` names this column as `table.column`, `schema.table.column` or `*.column`.
+    fn is_excluded(&self, table: &Table, col: &Column) -> bool {
+        let excluded = &self.options.excluded_columns;
+        !excluded.is_empty() && [
+            format!("{}.{}", table.qualified_name(), col.name),
+            format!("{}.{}", table.table_name, col.name),
+            format!("*.{}", col.name),
+        ]
+        .iter()
+        .any(|key| excluded.contains(key))
+    }
+    
+    /// Pairs the columns being inserted with the table's `ON CONFLICT` tail, if any.
+    fn insert_target<'t>(&self, table: &'t Table, included: Vec<usize>) -> InsertTarget<'t> {
+        let conflict = match self.options.on_conflict {
+            None => String::new(),
+            Some(_) if table.primary_keys.is_empty() => {
+                self.progress.suspend(|| {
+                    status!("   ⚠️ {} has no primary key; writing plain INSERTs without ON CONFLICT", table.table_name)
+                });
+                String::new()
+            }
+            Some(mode) => {
+                let keys: Vec<String> = table.primary_keys.iter().map(|k| quote_ident(k)).collect();
+                let updates: Vec<String> = included.iter()
+                    .map(|&idx| &table.columns[idx].name)
+                    .filter(|name| !table.primary_keys.contains(name))
+                    .map(|name| format!("{0} = EXCLUDED.{0}", quote_ident(name)))
+                    .collect();
+                // A table that is all key has nothing to update, so an upsert degrades to a skip.
+                if mode == OnConflict::Skip || updates.is_empty() {
+                    format!(" ON CONFLICT ({}) DO NOTHING", keys.join(", "))
+                } else {
+                    format!(" ON CONFLICT ({}) DO UPDATE SET {}", keys.join(", "), updates.join(", "))
+                }
+            }
+        };
+        InsertTarget { table, included, conflict }
+    }
+    
+    fn write_insert_header(writer: &mut impl Write, target: &InsertTarget) -> Result<()> {
+        let table = target.table;
+        let col_names: Vec<String> = target.included.iter().map(|&idx| quote_ident(&table.columns[idx].name)).collect();
+        writeln!(writer, "INSERT INTO {} ({}) VALUES", quote_table(&table.schema_name, &table.table_name), col_names.join(", "))?;
+        Ok(())
+    }
+    
+    /// Writes one row, opening a fresh INSERT every `batch_size` rows and closing each
+    /// statement (with its `ON CONFLICT` tail) at the end of its batch and of the table.
+    fn write_batched_row(writer: &mut impl Write, target: &InsertTarget, row_values: &[String], row_idx: usize, total_rows: usize, batch_size: usize) -> Result<()> {
+        if row_idx.is_multiple_of(batch_size) {
+            Self::write_insert_header(writer, target)?;
+        }
+        let values: Vec<&str> = target.included.iter().map(|&idx| row_values[idx].as_str()).collect();
+        if row_idx + 1 == total_rows || (row_idx + 1).is_multiple_of(batch_size) {
+            writeln!(writer, "({}){};", values.join(", "), target.conflict)?;
+        } else {
+            writeln!(writer, "({}),", values.join(", "))?;
+        }
+        Ok(())
+    }
+    
+    /// The planned semantic type of a column; columns missing from the plan are classified afresh.
+    fn semantic_type(&self, col: &Column, table: &Table) -> SemanticType {
+        self.semantic_types.get(&table.qualified_name())
+            .and_then(|types| types.get(&col.name))
+            .cloned()
+            .unwrap_or_else(|| analyze_column(col, table))
+    }
+    
+    /// With `sparse_columns`, draws a fill rate for each nullable column once for the table and
+    /// turns it into the first row that gets a value; every row before it is NULL. Keys and
+    /// overridden columns keep their usual handling.
+    fn sparse_fill_starts(&self, table: &Table, table_rows: usize) -> HashMap<String, usize> {
+        if !self.options.sparse_columns {
+            return HashMap::new();
+        }
+        let mut rng = self.rng();
+        table.columns.iter()
+            .filter(|col| col.is_nullable && !col.is_generated && !self.is_excluded(table, col))
+            .filter(|col| !matches!(self.semantic_type(col, table), SemanticType::PrimaryKey | SemanticType::ForeignKey(_)))
+            .filter(|col| !table.foreign_keys.iter().any(|fk| fk.column == col.name))
+            .filter(|col| !self.has_override(table, col, 0))
+            .map(|col| {
+                let fill_rate = rng.gen_range(SPARSE_FILL_RATES);
+                (col.name.clone(), (table_rows as f64 * (1.0 - fill_rate)).round() as usize)
+            })
+            .collect()
+    }
+    
+    /// Classifies the table's columns and fixes their generation order for `generate_table`.
+    fn analyze_table(&self, table: &Table, table_rows: usize) -> TableAnalysis {
+        let semantics: Vec<SemanticType> = table.columns.iter().map(|col| self.semantic_type(col, table)).collect();
+        let mut order: Vec<usize> = (0..semantics.len()).collect();
+        order.sort_by_key(|&idx| std::cmp::Reverse(DeepAnalyzer::get_generation_priority(&semantics[idx])));
+        TableAnalysis {
+            pk_index: semantics.iter().position(|s| *s == SemanticType::PrimaryKey),
+            first_filled_rows: self.sparse_fill_starts(table, table_rows),
+            semantics,
+            order,
+        }
+    }
+    
+    fn generate_intelligent_row(&self, table: &Table, analysis: &TableAnalysis, row_idx: usize) -> Result<Vec<String>> {
+        let mut context = ContextEngine::new(&table.qualified_name());
+        let mut temp_values: HashMap<String, String> = HashMap::new();
+        let mut rng = self.rng();
+        let fuzz_row = self.options.fuzz_rate > 0.0 && rng.gen_bool(self.options.fuzz_rate);
+        let first_filled_rows = &analysis.first_filled_rows;
+        
+        for &idx in &analysis.order {
+            let (col, semantic) = (&table.columns[idx], &analysis.semantics[idx]);
+            if let Some(value) = self.override_value(table, col, row_idx) {
+                self.update_context(&col.name, &value, semantic, &mut context);
+                temp_values.insert(col.name.clone(), value);
+                continue;
+            }
+            
+            // The parent of a deferred FK isn't generated yet; write_backpatch sets the real value.
+            if table.foreign_keys.iter().any(|fk| fk.deferred && fk.column == col.name) {
+                let placeholder = if col.is_nullable { "NULL".to_string() } else { self.generate_default(&col.data_type, row_idx) };
+                temp_values.insert(col.name.clone(), placeholder);
+                continue;
+            }
+            
+            if let Some(&first_filled) = first_filled_rows.get(&col.name) {
+                if row_idx < first_filled {
+                    temp_values.insert(col.name.clone(), "NULL".to_string());
+                    continue;
+                }
+            } else if col.is_nullable && *semantic != SemanticType::PrimaryKey && !self.shapes_tree(semantic, &context) {
+                // Unmeasured FKs follow their ON DELETE action: SET NULL leaves orphans behind,
+                // while CASCADE children never exist without their parent.
+                let default_rate = match table.foreign_keys.iter().find(|fk| fk.column == col.name) {
+                    Some(fk) if fk.sets_null() => self.options.null_probability.max(SET_NULL_ORPHAN_RATE),
+                    Some(fk) if fk.cascades() => 0.0,
+                    _ => self.options.null_probability,
+                };
+                let null_rate = col.observed_null_ratio.unwrap_or(default_rate);
+                if null_rate > 0.0 && rng.gen_bool(null_rate.min(1.0)) {
+                    temp_values.insert(col.name.clone(), "NULL".to_string());
+                    continue;
+                }
+            }
+            
+            // Keys stay intact under fuzzing so relationships still resolve.
+            let fuzzable = fuzz_row && !matches!(semantic, SemanticType::PrimaryKey | SemanticType::ForeignKey(_));
+            if fuzzable && rng.gen_bool(0.5) {
+                if let Some(value) = Fuzzer::edge_value(col, &mut rng) {
+                    temp_values.insert(col.name.clone(), value);
+                    continue;
+                }
+            }
+            
+            // Without a parent row the ForeignKey arm could only make up a dangling placeholder.
+            if let (true, SemanticType::ForeignKey(ref_table)) = (self.options.strict_fk, semantic) {
+                let no_parents = self.pk_storage.get(ref_table).is_none_or(|ids| ids.is_empty());
+                if no_parents && *ref_table != context.table && !col.is_nullable && col.array_element().is_none() {
+                    bail!("--strict-fk: {}.{} is NOT NULL but {} has no rows to reference", context.table, col.name, ref_table);
+                }
+            }
+            
+            // One anchor per row keeps country, state and city from the same place.
+            if matches!(semantic, SemanticType::Country | SemanticType::CountryCode | SemanticType::State | SemanticType::City) && context.place.is_none() {
+                context.place = self.place_candidates(&context, &col.name).and_then(|anchors| anchors.choose(&mut rng).copied());
+            }
+            
+            if matches!(semantic, SemanticType::Latitude | SemanticType::Longitude | SemanticType::GeoPoint | SemanticType::CoordinatePair)
+                && context.coordinates.is_none()
+            {
+                context.coordinates = Some(context.inherited_coordinates().unwrap_or_else(|| plausible_coordinates(&context, &mut rng)));
+            }
+            
+            if let (None, Some(element)) = (self.provider_for(col, semantic), col.array_element()) {
+                // One array doesn't describe the row the way a scalar does: it stays out of the context.
+                temp_values.insert(col.name.clone(), self.generate_array(semantic, &element, &context, row_idx));
+                continue;
+            }
+            
+            let value = match self.provider_for(col, semantic) {
+                Some(provider) => provider.generate(col, &context, &mut rng),
+                None => self.generate_by_semantic(semantic, col, &context, row_idx),
+            };
+            let value = self.finish_value(value, col);
+            self.update_context(&col.name, &value, semantic, &mut context);
+            temp_values.insert(col.name.clone(), value);
+        }
+        
+        let mut row_values: Vec<String> = table.columns.iter()
+            .map(|col| temp_values.get(&col.name).unwrap().clone())
+            .collect();
+        if let Some(hook) = &self.row_hook {
+            hook(table, &mut row_values, row_idx);
+        }
+        Ok(row_values)
+    }
+    
+    /// Anchors the row's place can come from: those agreeing with what the row already has,
+    /// narrowed to the country of `column`'s locale unless the row names a country of its own.
+    fn place_candidates(&self, ctx: &ContextEngine, column: &str) -> Option<Vec<&'static GeoAnchor>> {
+        let anchors = matching_anchors(ctx)?;
+        let Some(locale) = self.column_locale(&ctx.table, column).filter(|_| ctx.get("country").is_none()) else {
+            return Some(anchors);
+        };
+        let local: Vec<&GeoAnchor> = anchors.iter().copied().filter(|a| a.2 == locale.country()).collect();
+        Some(if local.is_empty() { anchors } else { local })
+    }
+    
+    /// Applies `--ascii-only` and the column's length limit to a generated literal.
+    fn finish_value(&self, value: String, col: &Column) -> String {
+        // Transliterate before fitting: `ß` becomes `ss`, so the ASCII form can be longer.
+        // Enum labels are fixed by the type and can't be respelled.
+        let value = if self.options.ascii_only && col.enum_labels.is_empty() { Self::transliterate(value) } else { value };
+        Self::fit_to_precision(Self::fit_to_length(value, col), col)
+    }
+    
+    /// Wraps a number that wouldn't fit the column's `numeric(p,s)` back into range (keeping
+    /// its low digits), so generic amounts don't overflow narrow columns.
+    fn fit_to_precision(value: String, col: &Column) -> String {
+        let (Some(precision), "numeric") = (col.numeric_precision, col.data_type.as_str()) else { return value };
+        let Ok(number) = value.parse::<f64>() else { return value };
+        let scale = col.numeric_scale.unwrap_or(0).clamp(0, precision);
+        let unit = 10f64.powi(scale);
+        let limit = 10f64.powi(precision - scale);
+        // Postgres rounds to the scale first, so 999.996 in numeric(5,2) already overflows.
+        if ((number * unit).round() / unit).abs() < limit {
+            return value;
+        }
+        let wrapped = ((number % limit) * unit).trunc() / unit;
+        format!("{:.*}", scale as usize, wrapped)
+    }
+    
+    /// An array literal (`'{"3","17"}'`) of up to four distinct elements, each produced the way
+    /// a scalar column of the element type would be: numeric scale and text limits hold, and
+    /// FK-backed id arrays draw from the referenced table's keys.
+    fn generate_array(&self, semantic: &SemanticType, element: &Column, ctx: &ContextEngine, row_idx: usize) -> String {
+        let mut rng = self.rng();
+        let mut items: Vec<String> = Vec::new();
+        for _ in 0..rng.gen_range(0..=4) {
+            let value = match semantic {
+                SemanticType::ForeignKey(ref_table) => self.get_fk_value(ref_table, element, &ctx.table),
+                _ => self.generate_by_semantic(semantic, element, ctx, row_idx),
+            };
+            let Some(raw) = sql_literal_to_raw(&self.finish_value(value, element)) else { continue };
+            let item = format!("\"{}\"", raw.replace('\\', "\\\\").replace('"', "\\\""));
+            if !items.contains(&item) {
+                items.push(item);
+            }
+        }
+        escape_sql_string(&format!("{{{}}}", items.join(",")))
+    }
+    
+    /// The first registered provider serving this column. Keys always stay with the
+    /// generator so references keep resolving.
+    fn provider_for(&self, col: &Column, semantic: &SemanticType) -> Option<&dyn Provider> {
+        if matches!(semantic, SemanticType::PrimaryKey | SemanticType::ForeignKey(_)) {
+            return None;
+        }
+        self.providers.iter()
+            .find(|(matcher, _)| matcher.matches(col, semantic))
+            .map(|(_, provider)| provider.as_ref())
+    }
+    
+    /// Rewrites a string literal in plain ASCII (`'José Müller'` → `'Jose Muller'`). Other
+    /// literals (numbers, `ARRAY[...]`) are left alone.
+    fn transliterate(value: String) -> String {
+        if value.is_ascii() || !(value.starts_with('\'') || value.starts_with("E'")) {
+            return value;
+        }
+        match sql_literal_to_raw(&value) {
+            Some(raw) => escape_sql_string(&deunicode(&raw)),
+            None => value,
+        }
+    }
+    
+    /// Cuts a string literal down to the column's `varchar(n)`/`char(n)` limit. Heuristic
+    /// generators don't know the limit, and without sampled values nothing else bounds them.
+    fn fit_to_length(value: String, col: &Column) -> String {
+        let Some(limit) = col.max_length.map(|n| n as usize) else { return value };
+        match sql_literal_to_raw(&value) {
+            Some(raw) if raw.chars().count() > limit => escape_sql_string(&raw.chars().take(limit).collect::<String>()),
+            _ => value,
+        }
+    }
+    
+    /// The value pinned for a column in this row: its fixture value, else its override.
+    fn override_value(&self, table: &Table, col: &Column, row_idx: usize) -> Option<String> {
+        let fixture = self.options.fixtures.get(&table.qualified_name())
+            .and_then(|rows| rows.get(row_idx))
+            .and_then(|row| row.get(&col.name));
+        if let Some(value) = fixture {
+            return Some(fixture_literal(value, col));
+        }
+        self.column_overrides(&table.qualified_name(), &col.name)
+            .find(|o| !matches!(o, ColumnOverride::Locale(_)))
+            .and_then(|o| o.value(col, row_idx, &mut self.rng()))
+    }
+    
+    /// Whether `override_value` would pin this row's value. Builds nothing and draws nothing,
+    /// so presence checks leave the seeded stream alone.
+    fn has_override(&self, table: &Table, col: &Column, row_idx: usize) -> bool {
+        let fixture = self.options.fixtures.get(&table.qualified_name())
+            .and_then(|rows| rows.get(row_idx))
+            .is_some_and(|row| row.contains_key(&col.name));
+        fixture || self.column_overrides(&table.qualified_name(), &col.name)
+            .any(|o| !matches!(o, ColumnOverride::Locale(_)))
+    }
+    
+    /// The locale a column's values are generated in: its own `locale:` override, else `--locale`.
+    fn column_locale(&self, table: &str, column: &str) -> Option<Locale> {
+        self.column_overrides(table, column)
+            .find_map(|o| match o {
+                ColumnOverride::Locale(locale) => Some(*locale),
+                _ => None,
+            })
+            .or(self.options.locale)
+    }
+    
+    /// Overrides naming `column` of the qualified `table`, most specific key first.
+    fn column_overrides(&self, table: &str, column: &str) -> impl Iterator<Item = &ColumnOverride> {
+        let table_name = table.split_once('.').map_or(table, |(_, name)| name);
+        let keys = if self.options.overrides.is_empty() {
+            Vec::new()
+        } else {
+            vec![format!("{}.{}", table, column), format!("{}.{}", table_name, column), format!("*.{}", column)]
+        };
+        keys.into_iter().filter_map(|key| self.options.overrides.get(&key))
+    }
+    
+    fn generate_by_semantic(&self, semantic: &SemanticType, col: &Column, ctx: &ContextEngine, row_idx: usize) -> String {
+        let mut rng = self.rng();
+        
+        if let Some(value) = ctx.inherited_location(semantic) {
+            return value.clone();
+        }
+        
+        match semantic {
+            SemanticType::ForeignKey(ref_table) if *ref_table == ctx.table => {
+                self.get_self_fk_value(ref_table, col, ctx, row_idx)
+            },
+            
+            SemanticType::ForeignKey(ref_table) => {
+                let value = self.get_fk_value(ref_table, col, &ctx.table);
+                // A nullable FK may stay NULL; a required one needs a placeholder at least.
+                if value == "NULL" && !col.is_nullable {
+                    return self.generate_default(&col.data_type, row_idx);
+                }
+                value
+            },
+            
+            SemanticType::PrimaryKey => {
+                // GENERATED ALWAYS keys are numbered by the database from 1, so the global offset can't apply.
+                let offset = match self.options.pk_offsets.get(&ctx.table) {
+                    Some(&offset) => offset,
+                    None if col.is_generated => 0,
+                    None => self.options.pk_offset,
+                };
+                let previous = self.pk_storage.get(&ctx.table)
+                    .and_then(|ids| ids.last())
+                    .and_then(|id| id.parse::<u64>().ok());
+                let key = match previous {
+                    // Gaps follow the last key actually emitted, so every later row shifts too.
+                    Some(last) if self.options.pk_gap_rate > 0.0 && !col.is_generated => {
+                        let gap = if rng.gen_bool(self.options.pk_gap_rate) { rng.gen_range(1..=5) } else { 0 };
+                        last + 1 + gap
+                    },
+                    _ => row_idx as u64 + 1 + offset,
+                };
+                // A text key holding UUIDs in the source keeps holding UUIDs.
+                let uuid_text = col.distinct_values.first().is_some_and(|v| Uuid::parse_str(v).is_ok());
+                if col.data_type.contains("uuid") || uuid_text {
+                    Self::key_literal(&random_uuid(&mut rng).to_string(), &col.data_type)
+                } else {
+                    Self::key_literal(&key.to_string(), &col.data_type)
+                }
+            },
+            
+            SemanticType::UUID => escape_sql_string(&random_uuid(&mut rng).to_string()),
+            SemanticType::BooleanValue => {
+                // Flags keep the source's true/false mix; unsampled ones lean true.
+                let value = rng.gen_bool(col.true_ratio.unwrap_or(0.75).clamp(0.0, 1.0));
+                match DeepAnalyzer::boolean_pair(&col.distinct_values) {
+                    // Keep the source's encoding: bare 1/0 for integers, 'Y'/'N' etc. for text.
+                    Some((t, f)) => {
+                        let encoded = if value { t } else { f };
+                        if col.data_type.contains("int") { encoded } else { escape_sql_string(&encoded) }
+                    },
+                    None => value.to_string(),
+                }
+            },
+            
+            SemanticType::FirstName => match ctx.gender {
+                Some(gender) => escape_sql_string(gender.first_name(&mut rng)),
+                None => escape_sql_string(&FirstName().fake_with_rng::<String, _>(&mut rng)),
+            },
+            SemanticType::LastName => escape_sql_string(&LastName().fake_with_rng::<String, _>(&mut rng)),
+            SemanticType::FullName => {
+                let locale = self.column_locale(&ctx.table, &col.name).unwrap_or(Locale::EnUs);
+                if let (Some(f), Some(l)) = (ctx.get("first_name"), ctx.get("last_name")) {
+                    escape_sql_string(&locale.full_name(f, l))
+                } else if let Some(gender) = ctx.gender {
+                    let last: String = LastName().fake_with_rng(&mut rng);
+                    escape_sql_string(&locale.full_name(gender.first_name(&mut rng), &last))
+                } else if locale.family_name_first() {
+                    let (first, last): (String, String) = (FirstName().fake_with_rng(&mut rng), LastName().fake_with_rng(&mut rng));
+                    escape_sql_string(&locale.full_name(&first, &last))
+                } else {
+                    escape_sql_string(&Name().fake_with_rng::<String, _>(&mut rng))
+                }
+            },
             
             SemanticType::Username => {
                 if let (Some(f), Some(l)) = (ctx.get("first_name"), ctx.get("last_name")) {
-                    format!("'{}.{}'", f.to_lowercase(), l.to_lowercase())
+                    escape_sql_string(&format!("{}.{}", f.to_lowercase(), l.to_lowercase()))
                 } else {
-                    format!("'user{}'", row_idx + 100000)
+                    escape_sql_string(&format!("user{}", row_idx + 100000))
                 }
             },
             
@@ -769,102 +3722,209 @@ impl Generator {
                 } else {
                     format!("user{}", row_idx + 100000)
                 };
+                let local = self.unique_email_local(&local);
+                // The source's own domain mix when it was sampled, else the big public providers.
+                if let Ok((domain, _)) = col.email_domains.choose_weighted(&mut rng, |(_, share)| *share) {
+                    return escape_sql_string(&format!("{}@{}", local, domain));
+                }
                 let providers = ["gmail.com", "yahoo.com", "outlook.com", "hotmail.com", "icloud.com"];
-                format!("'{}@{}'", local, providers.choose(&mut rng).unwrap())
+                escape_sql_string(&format!("{}@{}", local, providers.choose(&mut rng).unwrap()))
             },
             
             SemanticType::Gender => {
+                // Reuse the source's own coding (M/F, Male/Female, ...) when it was sampled.
+                if let Some(sample) = col.distinct_values.choose(&mut rng) {
+                    return escape_sql_string(sample);
+                }
                 let genders = ["male", "female", "other"];
-                format!("'{}'", genders.choose(&mut rng).unwrap())
+                let gender = genders.choose(&mut rng).unwrap();
+                if col.max_length == Some(1) {
+                    escape_sql_string(&gender[..1].to_uppercase())
+                } else {
+                    escape_sql_string(gender)
+                }
             },
             
-            SemanticType::Age => rng.gen_range(18..75).to_string(),
+            SemanticType::Age => match ctx.birth_date {
+                Some(birth) => age_on(birth, Utc::now().date_naive()).to_string(),
+                None => rng.gen_range(18..75).to_string(),
+            },
             SemanticType::BirthDate => {
-                let years = rng.gen_range(18..70);
-                format!("'{}'", (Utc::now() - Duration::days(years * 365)).format("%Y-%m-%d"))
+                let days = rng.gen_range(18 * 365..70 * 365);
+                escape_sql_string(&(Utc::now() - Duration::days(days)).format("%Y-%m-%d").to_string())
             },
             
             SemanticType::CompanyName | SemanticType::OrganizationName => {
-                format!("'{}'", CompanyName().fake::<String>().replace("'", "''"))
+                escape_sql_string(&CompanyName().fake_with_rng::<String, _>(&mut rng))
             },
             
             SemanticType::MerchantName => {
                 if let Some(company) = ctx.get("company_name") {
-                    let variants = vec![
+                    let variants = [
                         format!("{} Store", company),
                         format!("{} Market", company),
-                        CompanyName().fake::<String>(),
+                        CompanyName().fake_with_rng::<String, _>(&mut rng),
                     ];
-                    format!("'{}'", variants.choose(&mut rng).unwrap().replace("'", "''"))
+                    escape_sql_string(variants.choose(&mut rng).unwrap())
                 } else {
-                    format!("'{}'", CompanyName().fake::<String>().replace("'", "''"))
+                    escape_sql_string(&CompanyName().fake_with_rng::<String, _>(&mut rng))
                 }
             },
             
-            SemanticType::Country => format!("'{}'", CountryName().fake::<String>().replace("'", "''")),
-            SemanticType::State => format!("'{}'", StateName().fake::<String>().replace("'", "''")),
-            SemanticType::City => format!("'{}'", CityName().fake::<String>().replace("'", "''")),
+            SemanticType::Country => escape_sql_string(&ctx.place.map_or_else(|| CountryName().fake_with_rng(&mut rng), |a| a.2.to_string())),
+            SemanticType::CountryCode => {
+                let country = ctx.place.map(|a| a.2.to_string())
+                    .or_else(|| ctx.get("country").cloned())
+                    .or_else(|| ctx.inherited_location(&SemanticType::Country).and_then(|v| sql_literal_to_raw(v)));
+                let entry = country.and_then(|name| iso_country(&name))
+                    .unwrap_or_else(|| COUNTRY_CODES.choose(&mut rng).unwrap());
+                let name = col.name.to_lowercase();
+                let alpha3 = col.max_length == Some(3) || ["alpha3", "iso3", "alpha_3", "iso_3"].iter().any(|k| name.contains(k));
+                escape_sql_string(if alpha3 { entry.2 } else { entry.1 })
+            },
+            SemanticType::State => escape_sql_string(&ctx.place.map_or_else(|| StateName().fake_with_rng(&mut rng), |a| a.1.to_string())),
+            SemanticType::City => escape_sql_string(&ctx.place.map_or_else(|| CityName().fake_with_rng(&mut rng), |a| a.0.to_string())),
             SemanticType::StreetAddress => {
-                let streets = ["Main St", "Oak Ave", "Maple Dr", "Pine Rd", "Elm St", "Park Blvd", "Broadway", "Market St"];
-                format!("'{} {}'", rng.gen_range(100..9999), streets.choose(&mut rng).unwrap())
+                escape_sql_string(&self.column_locale(&ctx.table, &col.name).unwrap_or(Locale::EnUs).street_address(&mut rng))
             },
-            SemanticType::PostalCode => format!("'{}'", ZipCode().fake::<String>()),
+            SemanticType::PostalCode => escape_sql_string(&self.column_locale(&ctx.table, &col.name).unwrap_or(Locale::EnUs).postal_code(&mut rng)),
             
-            SemanticType::Latitude | SemanticType::GalacticCoordinate => {
-                format!("{:.6}", rng.gen_range(-90.0..90.0))
+            SemanticType::Latitude => {
+                let (lat, _) = ctx.coordinates.unwrap_or_else(|| plausible_coordinates(ctx, &mut rng));
+                format!("{:.6}", lat)
             },
             SemanticType::Longitude => {
-                format!("{:.6}", rng.gen_range(-180.0..180.0))
+                let (_, lon) = ctx.coordinates.unwrap_or_else(|| plausible_coordinates(ctx, &mut rng));
+                format!("{:.6}", lon)
+            },
+            SemanticType::GeoPoint => {
+                let (lat, lon) = ctx.coordinates.unwrap_or_else(|| plausible_coordinates(ctx, &mut rng));
+                match postgis_point(&col.sql_type) {
+                    // EWKT keeps CSV output loadable; COPY can't evaluate function calls.
+                    Some(_) if self.options.format == OutputFormat::Csv => escape_sql_string(&format!("SRID=4326;POINT({:.6} {:.6})", lon, lat)),
+                    Some(PostgisPoint { geography: true, .. }) => format!("ST_SetSRID(ST_MakePoint({:.6}, {:.6}), 4326)::geography", lon, lat),
+                    Some(PostgisPoint { srid, .. }) if srid != 0 && srid != 4326 => {
+                        format!("ST_Transform(ST_SetSRID(ST_MakePoint({:.6}, {:.6}), 4326), {})", lon, lat, srid)
+                    },
+                    Some(_) => format!("ST_SetSRID(ST_MakePoint({:.6}, {:.6}), 4326)", lon, lat),
+                    None => escape_sql_string(&format!("({:.6},{:.6})", lon, lat)),
+                }
+            },
+            SemanticType::CoordinatePair => {
+                let (lat, lon) = ctx.coordinates.unwrap_or_else(|| plausible_coordinates(ctx, &mut rng));
+                // Keep the source's separator, "lat,long" or "lat, long".
+                let separator = if col.distinct_values.iter().any(|v| v.contains(", ")) { ", " } else { "," };
+                escape_sql_string(&format!("{:.6}{}{:.6}", lat, separator, lon))
+            },
+            SemanticType::GalacticCoordinate => {
+                format!("{:.6}", rng.gen_range(-90.0..90.0))
             },
             
             SemanticType::PhoneNumber | SemanticType::MobileNumber => {
+                if let Some(locale) = self.column_locale(&ctx.table, &col.name) {
+                    return escape_sql_string(&locale.phone_number(&mut rng));
+                }
                 let codes = ["+1", "+44", "+61", "+91"];
-                format!("'{}-{}-{}-{}'", 
+                escape_sql_string(&format!("{}-{}-{}-{}", 
                     codes.choose(&mut rng).unwrap(),
                     rng.gen_range(200..999),
                     rng.gen_range(200..999),
                     rng.gen_range(1000..9999)
-                )
+                ))
             },
             
             SemanticType::DomainName => {
                 if let Some(company) = ctx.get("company_name") {
                     let clean: String = company.chars().filter(|c| c.is_alphanumeric()).collect();
-                    format!("'{}.com'", clean.to_lowercase())
+                    escape_sql_string(&format!("{}.com", clean.to_lowercase()))
                 } else {
-                    format!("'example{}.com'", rng.gen_range(1000..9999))
+                    escape_sql_string(&format!("example{}.com", rng.gen_range(1000..9999)))
                 }
             },
             
             SemanticType::URL => {
                 if let Some(domain) = ctx.get("domain") {
-                    format!("'https://www.{}'", domain)
+                    escape_sql_string(&format!("https://www.{}", domain))
                 } else {
-                    format!("'https://www.example{}.com'", rng.gen_range(1000..9999))
+                    escape_sql_string(&format!("https://www.example{}.com", rng.gen_range(1000..9999)))
                 }
             },
+            SemanticType::AvatarURL | SemanticType::ImageURL | SemanticType::ProfileURL | SemanticType::WebhookURL => {
+                let domain = ctx.get("domain").cloned().unwrap_or_else(|| format!("example{}.com", rng.gen_range(1000..9999)));
+                let url = match semantic {
+                    SemanticType::AvatarURL => format!("https://cdn.{}/avatars/{}.png", domain, random_uuid(&mut rng)),
+                    SemanticType::ImageURL => {
+                        let extension = ["jpg", "png", "webp"].choose(&mut rng).unwrap();
+                        let id = random_uuid(&mut rng).simple().to_string();
+                        format!("https://images.{}/{}/{:02}/{}.{}", domain, rng.gen_range(2019..=2025), rng.gen_range(1..=12), &id[..16], extension)
+                    },
+                    SemanticType::ProfileURL => {
+                        let handle = match (ctx.get("username"), ctx.get("first_name"), ctx.get("last_name")) {
+                            (Some(username), _, _) => username.clone(),
+                            (None, Some(f), Some(l)) => format!("{}.{}", f, l),
+                            _ => format!("user{}", row_idx + 100000),
+                        };
+                        let handle: String = handle.to_lowercase().chars().filter(|c| c.is_alphanumeric() || matches!(c, '.' | '_' | '-')).collect();
+                        format!("https://www.{}/users/{}", domain, handle)
+                    },
+                    _ => format!("https://api.{}/webhooks/{}", domain, random_uuid(&mut rng).simple()),
+                };
+                escape_sql_string(&url)
+            },
             
             SemanticType::DateSigned | SemanticType::DateEstablished | SemanticType::DateLaunched | 
             SemanticType::DateCreated | SemanticType::DateRegistered | SemanticType::DateStart => {
-                let days_ago = rng.gen_range(365..1825);
-                format!("'{}'", (Utc::now() - Duration::days(days_ago)).format("%Y-%m-%d"))
+                let ago = Duration::days(rng.gen_range(365..1825)) + Duration::microseconds(rng.gen_range(0..86_400_000_000));
+                escape_sql_string(&date_text(Utc::now() - ago, col))
             },
             
             SemanticType::DateEnd => {
-                let base = ctx.get_any_start_date()
-                    .unwrap_or_else(|| Utc::now().naive_utc().date() - Duration::days(rng.gen_range(365..730)));
-                let days_add = rng.gen_range(30..730);
-                format!("'{}'", (base + Duration::days(days_add)).format("%Y-%m-%d"))
+                let base = ctx.get_any_start_date().map(|start| start.and_utc())
+                    .unwrap_or_else(|| Utc::now() - Duration::days(rng.gen_range(365..730)));
+                let later = Duration::days(rng.gen_range(30..730)) + Duration::microseconds(rng.gen_range(0..86_400_000_000));
+                escape_sql_string(&date_text(base + later, col))
             },
             
             SemanticType::DateUpdated => {
-                let days_ago = rng.gen_range(1..90);
-                format!("'{}'", (Utc::now() - Duration::days(days_ago)).format("%Y-%m-%d"))
+                let now = Utc::now();
+                let mut updated = now - Duration::days(rng.gen_range(1..90)) - Duration::microseconds(rng.gen_range(0..86_400_000_000));
+                if let Some(created) = ctx.latest_date().map(|created| created.and_utc()).filter(|created| updated < *created + Duration::seconds(1)) {
+                    // Recently created rows: pick a moment between creation and now instead, at least
+                    // a second on so a coarser column precision can't truncate it below creation.
+                    let span = (now - created).num_seconds().max(1);
+                    updated = created + Duration::seconds(rng.gen_range(1..=span));
+                }
+                escape_sql_string(&date_text(updated, col))
             },
             
             SemanticType::Timestamp => {
-                let days_ago = rng.gen_range(0..365);
-                format!("'{}'", (Utc::now() - Duration::days(days_ago)).format("%Y-%m-%d %H:%M:%S"))
+                let ago = Duration::days(rng.gen_range(0..365)) + Duration::microseconds(rng.gen_range(0..86_400_000_000));
+                escape_sql_string(&timestamp_text(Utc::now() - ago, col.datetime_precision))
+            },
+            
+            SemanticType::Date => {
+                let days_ago = rng.gen_range(0..1825);
+                escape_sql_string(&(Utc::now() - Duration::days(days_ago)).format("%Y-%m-%d").to_string())
+            },
+            
+            SemanticType::TimeOfDay => {
+                escape_sql_string(&format!("{:02}:{:02}:{:02}", rng.gen_range(0..24), rng.gen_range(0..60), rng.gen_range(0..60)))
+            },
+            
+            SemanticType::Interval => {
+                escape_sql_string(&format!("{} days {:02}:{:02}:{:02}",
+                    rng.gen_range(0..30),
+                    rng.gen_range(0..24),
+                    rng.gen_range(0..60),
+                    rng.gen_range(0..60)
+                ))
+            },
+            
+            SemanticType::PublicIPv4Address => escape_sql_string(&public_ipv4(&mut rng)),
+            
+            SemanticType::IPv4Address if self.options.public_ips
+                && !INTERNAL_IP_HINTS.iter().any(|k| col.name.to_lowercase().contains(k)) => {
+                escape_sql_string(&public_ipv4(&mut rng))
             },
             
             SemanticType::IPv4Address => {
@@ -874,124 +3934,166 @@ impl Generator {
                     (192, 168, rng.gen_range(0..256), rng.gen_range(1..255)),
                 ];
                 let ip = ranges.choose(&mut rng).unwrap();
-                format!("'{}.{}.{}.{}'", ip.0, ip.1, ip.2, ip.3)
+                escape_sql_string(&format!("{}.{}.{}.{}", ip.0, ip.1, ip.2, ip.3))
+            },
+            
+            SemanticType::NetworkCidr => {
+                // cidr rejects set host bits, so the address is masked down to its prefix.
+                let prefix = rng.gen_range(8..=30u32);
+                let base: u32 = if self.options.public_ips {
+                    public_ipv4(&mut rng).parse::<std::net::Ipv4Addr>().map(u32::from).unwrap_or(0)
+                } else {
+                    (10 << 24) | rng.gen_range(0..1 << 24)
+                };
+                let network = std::net::Ipv4Addr::from(base & (u32::MAX << (32 - prefix)));
+                escape_sql_string(&format!("{}/{}", network, prefix))
             },
             
             SemanticType::IPv6Address => {
                 let segs: Vec<String> = (0..8).map(|_| format!("{:04x}", rng.gen_range(0..65536))).collect();
-                format!("'{}'", segs.join(":"))
+                escape_sql_string(&segs.join(":"))
             },
             
             SemanticType::MacAddress => {
-                format!("'{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}'",
+                escape_sql_string(&format!("{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
                     rng.gen_range(0..256), rng.gen_range(0..256), rng.gen_range(0..256),
-                    rng.gen_range(0..256), rng.gen_range(0..256), rng.gen_range(0..256))
+                    rng.gen_range(0..256), rng.gen_range(0..256), rng.gen_range(0..256)))
             },
             
             SemanticType::NetworkPort => rng.gen_range(1024..65535).to_string(),
             
             SemanticType::MoneyAmount | SemanticType::PriceValue | SemanticType::BalanceValue | SemanticType::CreditValue => {
-                format!("{:.2}", rng.gen_range(100.0..99999.99))
+                let digits = ctx.get("currency").map_or(2, |code| currency_minor_units(code));
+                // The column's declared scale still caps the digits (e.g. BTC into numeric(12,2)).
+                let digits = col.numeric_scale.map_or(digits, |scale| digits.min(scale.max(0) as usize));
+                format!("{:.*}", digits, rng.gen_range(100.0..99999.99))
+            },
+            
+            SemanticType::CreditCardNumber => {
+                let number = test_card_number(&mut rng);
+                let numeric = ["int", "numeric", "decimal"].iter().any(|t| col.data_type.contains(t));
+                if numeric { number } else { escape_sql_string(&number) }
+            },
+            
+            SemanticType::IBAN => {
+                // The row's country, else the locale's, decides the layout when it has one.
+                let country = ctx.get("country").map(String::as_str)
+                    .or(self.column_locale(&ctx.table, &col.name).map(Locale::country))
+                    .and_then(iso_country);
+                escape_sql_string(&iban(country.map(|c| c.1), col.max_length, &mut rng))
             },
             
             SemanticType::CurrencyCode => {
-                let currencies = ["USD", "EUR", "GBP", "JPY", "AUD", "CAD"];
-                format!("'{}'", currencies.choose(&mut rng).unwrap())
+                // The row's country pays in its own currency; otherwise any ISO 4217 code from the table.
+                let local = ctx.get("country").and_then(|name| iso_country(name));
+                let entry = local.unwrap_or_else(|| COUNTRY_CODES.choose(&mut rng).unwrap());
+                escape_sql_string(entry.3)
             },
             
             SemanticType::WalletAddress => {
                 if rng.gen_bool(0.6) {
                     let hex: String = (0..40).map(|_| format!("{:x}", rng.gen_range(0..16))).collect();
-                    format!("'0x{}'", hex)
+                    escape_sql_string(&format!("0x{}", hex))
                 } else {
                     let chars = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
                     let addr: String = (0..33)
                         .map(|_| chars.chars().nth(rng.gen_range(0..chars.len())).unwrap())
                         .collect();
-                    format!("'1{}'", addr)
+                    escape_sql_string(&format!("1{}", addr))
                 }
             },
             
             SemanticType::HashValue | SemanticType::EncryptionKey | SemanticType::TokenValue => {
                 let hash: String = (0..32).map(|_| format!("{:x}", rng.gen_range(0..16))).collect();
-                format!("'{}'", hash)
+                escape_sql_string(&hash)
             },
             
             SemanticType::StatusActive | SemanticType::OperationalStatus => {
                 let statuses = ["active", "inactive", "pending", "completed", "cancelled", "processing"];
-                format!("'{}'", statuses.choose(&mut rng).unwrap())
+                escape_sql_string(statuses.choose(&mut rng).unwrap())
             },
             
             SemanticType::TypeCategory | SemanticType::ClassLevel => {
                 let types = ["standard", "basic", "advanced", "premium", "professional"];
-                format!("'{}'", types.choose(&mut rng).unwrap())
+                escape_sql_string(types.choose(&mut rng).unwrap())
             },
             
             SemanticType::SkillLevel | SemanticType::SecurityLevel | SemanticType::ClearanceLevel | SemanticType::RankTitle => {
                 let levels = ["beginner", "intermediate", "advanced", "expert", "master"];
-                format!("'{}'", levels.choose(&mut rng).unwrap())
+                escape_sql_string(levels.choose(&mut rng).unwrap())
             },
             
             SemanticType::PriorityLevel => {
                 let priorities = ["low", "medium", "high", "critical", "urgent"];
-                format!("'{}'", priorities.choose(&mut rng).unwrap())
+                escape_sql_string(priorities.choose(&mut rng).unwrap())
+            },
+            
+            SemanticType::EnumLabel => match col.enum_labels.choose(&mut rng) {
+                Some(label) => escape_sql_string(label),
+                None => "NULL".to_string(),
             },
             
             SemanticType::TrackingCode | SemanticType::SerialNumber | SemanticType::BadgeID | 
             SemanticType::SKUCode | SemanticType::ReferenceID => {
                 let prefix: String = (b'A'..=b'Z').map(|c| c as char).collect::<Vec<_>>()
                     .choose_multiple(&mut rng, 3).collect();
-                format!("'{}-{}-{}'", prefix, rng.gen_range(1000..9999), rng.gen_range(100..999))
+                escape_sql_string(&format!("{}-{}-{}", prefix, rng.gen_range(1000..9999), rng.gen_range(100..999)))
             },
             
             SemanticType::SectorName | SemanticType::OutpostName | SemanticType::PlanetName | SemanticType::StationName => {
                 let prefixes = ["Alpha", "Beta", "Gamma", "Delta", "Epsilon", "Zeta", "Theta", "Omega"];
                 let suffixes = ["Prime", "Station", "Base", "Colony", "Outpost", "Hub"];
-                format!("'{} {}'", prefixes.choose(&mut rng).unwrap(), suffixes.choose(&mut rng).unwrap())
+                escape_sql_string(&format!("{} {}", prefixes.choose(&mut rng).unwrap(), suffixes.choose(&mut rng).unwrap()))
             },
             
             SemanticType::SpecimenName => {
                 let names = ["alpha", "beta", "gamma", "delta", "epsilon", "zeta", "theta", "omega"];
-                format!("'{}'", names.choose(&mut rng).unwrap())
+                escape_sql_string(names.choose(&mut rng).unwrap())
             },
             
             SemanticType::JurisdictionZone => {
                 let zones = ["Alpha Sector", "Beta Quadrant", "Gamma Region", "Delta Zone", "Epsilon District"];
-                format!("'{}'", zones.choose(&mut rng).unwrap())
+                escape_sql_string(zones.choose(&mut rng).unwrap())
             },
             
             SemanticType::HazardClassification => {
                 let classes = ["Level-1", "Level-2", "Level-3", "Level-4", "Level-5", "Biohazard", "Chemical", "Radiation", "Toxic"];
-                format!("'{}'", classes.choose(&mut rng).unwrap())
+                escape_sql_string(classes.choose(&mut rng).unwrap())
             },
             
             SemanticType::TitleText => {
-                let text: String = (3..8).fake();
-                format!("'{}'", text.replace("'", "''"))
+                let text: String = (3..8).fake_with_rng(&mut rng);
+                escape_sql_string(&text)
+            },
+            
+            SemanticType::DescriptionText | SemanticType::SummaryText |
+            SemanticType::BodyContent | SemanticType::CommentText | SemanticType::NotesText
+                if col.len_stats.is_some() => {
+                escape_sql_string(&Self::text_like_source(col, &mut rng))
             },
             
             SemanticType::DescriptionText | SemanticType::SummaryText => {
-                let text: String = (10..30).fake();
-                format!("'{}'", text.replace("'", "''"))
+                let text: String = (10..30).fake_with_rng(&mut rng);
+                escape_sql_string(&text)
             },
             
             SemanticType::BodyContent | SemanticType::CommentText | SemanticType::NotesText => {
-                let text: String = (20..60).fake();
-                format!("'{}'", text.replace("'", "''"))
+                let text: String = (20..60).fake_with_rng(&mut rng);
+                escape_sql_string(&text)
             },
             
             SemanticType::FilePath => {
                 let exts = ["dat", "bin", "tmp", "log", "txt"];
                 let folders = ["/uploads", "/media", "/files", "/storage", "/data"];
-                format!("'{}/{}.{}'", 
+                escape_sql_string(&format!("{}/{}.{}", 
                     folders.choose(&mut rng).unwrap(),
-                    Uuid::new_v4(),
+                    random_uuid(&mut rng),
                     exts.choose(&mut rng).unwrap()
-                )
+                ))
             },
             
             SemanticType::FirmwareVersion | SemanticType::SoftwareVersion => {
-                format!("'{}.{}.{}'", rng.gen_range(1..10), rng.gen_range(0..20), rng.gen_range(0..100))
+                escape_sql_string(&format!("{}.{}.{}", rng.gen_range(1..10), rng.gen_range(0..20), rng.gen_range(0..100)))
             },
             
             SemanticType::WeightMetric => format!("{:.2}", rng.gen_range(50.0..10000.0)),
@@ -1001,44 +4103,138 @@ impl Generator {
             SemanticType::DurationHours => rng.gen_range(1..500).to_string(),
             SemanticType::ByteSize => rng.gen_range(100..10000).to_string(),
             
+            SemanticType::Percentage => {
+                if col.data_type.contains("int") {
+                    return rng.gen_range(0..=100).to_string();
+                }
+                let (max, digits) = percentage_scale(col);
+                format!("{:.*}", digits, rng.gen_range(0.0..=max))
+            },
+            
             SemanticType::IntegerValue => rng.gen_range(1..10000).to_string(),
             SemanticType::DecimalValue => format!("{:.2}", rng.gen_range(0.0..9999.99)),
             
+            SemanticType::BinaryData => {
+                let bytes: Vec<String> = (0..self.options.bytea_length).map(|_| format!("{:02x}", rng.gen::<u8>())).collect();
+                bytea_literal(&bytes.concat())
+            },
+            
             SemanticType::JSONValue => {
-                format!("'{{\"id\": \"{}\", \"status\": \"active\"}}'", Uuid::new_v4())
+                escape_sql_string(&format!("{{\"id\": \"{}\", \"status\": \"active\"}}", random_uuid(&mut rng)))
+            },
+            // The server parses xml on insert, so this has to be a well-formed document.
+            SemanticType::XMLValue => {
+                let status = ["active", "pending", "archived"].choose(&mut rng).unwrap();
+                escape_sql_string(&format!("<record id=\"{}\"><status>{}</status></record>", random_uuid(&mut rng), status))
+            },
+            // `"key"=>"value"` pairs, both sides quoted with `"` and `\` backslash-escaped.
+            SemanticType::HstoreValue => {
+                let attributes: [(&str, &[&str]); 8] = [
+                    ("color", &["red", "blue", "green", "black"]),
+                    ("size", &["S", "M", "L", "XL"]),
+                    ("source", &["web", "ios", "android", "api"]),
+                    ("tier", &["free", "silver", "gold"]),
+                    ("region", &["us-east", "eu-west", "ap-south"]),
+                    ("channel", &["email", "sms", "push"]),
+                    ("theme", &["light", "dark"]),
+                    ("beta", &["true", "false"]),
+                ];
+                let quote = |s: &str| format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""));
+                let count = rng.gen_range(1..=4);
+                let chosen: Vec<&(&str, &[&str])> = attributes.choose_multiple(&mut rng, count).collect();
+                let pairs: Vec<String> = chosen.iter()
+                    .map(|(key, values)| format!("{}=>{}", quote(key), quote(values.choose(&mut rng).unwrap())))
+                    .collect();
+                escape_sql_string(&pairs.join(","))
+            },
+            SemanticType::RangeValue => match RangeKind::of(&col.data_type) {
+                Some(kind) => {
+                    let start = rng.gen_range(kind.window());
+                    let (_, length) = kind.span(&mut rng);
+                    escape_sql_string(&kind.literal(self.started_at, start, start + length))
+                }
+                None => "'default'".to_string(),
             },
             
             SemanticType::TextValue => {
                 let words = ["alpha", "beta", "gamma", "delta", "epsilon"];
-                format!("'{}'", words.choose(&mut rng).unwrap())
+                escape_sql_string(words.choose(&mut rng).unwrap())
             },
             
             _ => "'default'".to_string(),
         }
     }
     
+    /// Lorem text whose length follows the source column's (min, avg, max) character lengths,
+    /// drawn from a triangular distribution peaking at the average.
+    fn text_like_source(col: &Column, rng: &mut impl Rng) -> String {
+        let (min, avg, max) = col.len_stats.unwrap_or((10, 30, 60));
+        let max = col.max_length.map_or(max, |limit| max.min(limit as usize));
+        let min = min.min(max);
+        let target = match Triangular::new(min as f64, max as f64, (avg.clamp(min, max)) as f64) {
+            Ok(dist) if min < max => dist.sample(rng).round() as usize,
+            _ => max,
+        };
+
+        let mut text = String::new();
+        while text.chars().count() < target {
+            let words: Vec<String> = Words(3..10).fake_with_rng(rng);
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(&words.join(" "));
+        }
+        text.chars().take(target).collect::<String>().trim_end().to_string()
+    }
+    
     fn update_context(&self, field: &str, value: &str, semantic: &SemanticType, ctx: &mut ContextEngine) {
-        let clean = value.trim_matches('\'').to_string();
-        if clean == "NULL" || clean.is_empty() {
+        let Some(clean) = sql_literal_to_raw(value).filter(|v| !v.is_empty()) else {
             return;
-        }
+        };
         
         ctx.set(&field.to_lowercase(), &clean);
         
         match semantic {
+            SemanticType::PrimaryKey => ctx.row_pk = Some(value.to_string()),
             SemanticType::FirstName => ctx.set("first_name", &clean),
             SemanticType::LastName => ctx.set("last_name", &clean),
             SemanticType::FullName => {
+                // Usernames and emails built from the context want the given name first,
+                // whichever way round the locale writes it.
                 let parts: Vec<&str> = clean.split_whitespace().collect();
                 if parts.len() >= 2 {
-                    ctx.set("first_name", parts[0]);
-                    ctx.set("last_name", parts.last().unwrap());
+                    let (mut given, mut family) = (parts[0], *parts.last().unwrap());
+                    if self.column_locale(&ctx.table, field).is_some_and(Locale::family_name_first) {
+                        std::mem::swap(&mut given, &mut family);
+                    }
+                    ctx.set("first_name", given);
+                    ctx.set("last_name", family);
                 }
             },
             SemanticType::CompanyName => ctx.set("company_name", &clean),
+            SemanticType::Gender => ctx.gender = NameGender::parse(&clean),
+            SemanticType::BirthDate => ctx.birth_date = clean.get(..10).and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok()),
+            SemanticType::Country => ctx.set("country", &clean),
+            SemanticType::CountryCode => {
+                if let Some(entry) = iso_country(&clean) {
+                    ctx.set("country", entry.0);
+                }
+            },
+            SemanticType::State => ctx.set("state", &clean),
+            SemanticType::CurrencyCode => ctx.set("currency", &clean),
+            SemanticType::City => ctx.set("city", &clean),
+            SemanticType::ForeignKey(ref_table) if ctx.parent_location.is_none() => {
+                if let Some(location) = self.location_storage.get(ref_table).and_then(|m| m.get(&clean)) {
+                    ctx.parent_location = Some(location.clone());
+                }
+            },
             SemanticType::DomainName => ctx.set("domain", &clean),
-            SemanticType::DateSigned | SemanticType::DateEstablished | SemanticType::DateCreated | SemanticType::DateStart => {
-                if let Ok(date) = NaiveDate::parse_from_str(&clean, "%Y-%m-%d") {
+            SemanticType::Username => ctx.set("username", &clean),
+            SemanticType::DateSigned | SemanticType::DateEstablished | SemanticType::DateCreated | SemanticType::DateStart |
+            SemanticType::DateRegistered | SemanticType::DateLaunched => {
+                let date = NaiveDateTime::parse_from_str(&clean, "%Y-%m-%d %H:%M:%S%.f")
+                    .or_else(|_| NaiveDate::parse_from_str(&clean, "%Y-%m-%d").map(|d| d.and_time(Default::default())));
+                if let Ok(date) = date {
                     ctx.set_date(&field.to_lowercase(), date);
                 }
             },
@@ -1046,27 +4242,141 @@ impl Generator {
         }
     }
     
-    fn get_fk_value(&self, ref_table: &str, dtype: &str) -> String {
-        let mut rng = rand::thread_rng();
-        
-        if let Some(ids) = self.pk_storage.get(ref_table) {
-            if !ids.is_empty() {
-                let id = ids.choose(&mut rng).unwrap();
-                if dtype.contains("uuid") || dtype.contains("char") || dtype.contains("text") {
-                    return format!("'{}'", id);
-                }
-                return id.clone();
+    /// Strips characters that don't belong in a mailbox name and appends a run-wide counter
+    /// on repeats (`john.smith`, `john.smith1`, `john.smith2`, ...) so unique-email constraints hold.
+    fn unique_email_local(&self, local: &str) -> String {
+        let clean: String = local.chars().filter(|c| c.is_alphanumeric() || *c == '.').collect();
+        let mut seen = self.email_locals.borrow_mut();
+        let mut suffix = seen.get(&clean).copied().unwrap_or(0);
+        loop {
+            let candidate = if suffix == 0 { clean.clone() } else { format!("{}{}", clean, suffix) };
+            suffix += 1;
+            if !seen.contains_key(&candidate) {
+                seen.insert(clean.clone(), suffix);
+                seen.entry(candidate.clone()).or_insert(1);
+                return candidate;
             }
         }
+    }
+    
+    fn get_fk_value(&self, ref_table: &str, col: &Column, table: &str) -> String {
+        let mut rng = self.rng();
+        
+        if self.options.null_optional_fks && col.is_nullable {
+            return "NULL".to_string();
+        }
+        let Some(ids) = self.pk_storage.get(ref_table).filter(|ids| !ids.is_empty()) else {
+            return "NULL".to_string();
+        };
+        
+        let cursor = format!("{}.{}", table, col.name);
+        let idx = match self.options.fk_distribution {
+            // Cascading children belong to their parent: link each parent once before repeating.
+            FkDistribution::Uniform if self.cascading_fks.contains(&cursor) => {
+                self.next_unused_parent(&cursor, ids.len()).unwrap_or_else(|| rng.gen_range(0..ids.len()))
+            },
+            FkDistribution::Uniform => rng.gen_range(0..ids.len()),
+            FkDistribution::Zipf => {
+                // Rank 1 (the first parent) is the most popular, with a long tail behind it.
+                let zipf = Zipf::new(ids.len() as u64, 1.0).expect("zipf needs at least one parent");
+                (zipf.sample(&mut rng) as usize).clamp(1, ids.len()) - 1
+            },
+            FkDistribution::OneToOne => match self.next_unused_parent(&cursor, ids.len()) {
+                Some(idx) => idx,
+                // Every parent is taken: leave optional links empty, reuse parents otherwise.
+                None if col.is_nullable => return "NULL".to_string(),
+                None => rng.gen_range(0..ids.len()),
+            },
+        };
+        
+        Self::key_literal(&ids[idx], &col.data_type)
+    }
+    
+    /// Walks the `parents` rows in a shuffled order kept per FK column (`cursor`), returning
+    /// `None` once each has been handed out.
+    fn next_unused_parent(&self, cursor: &str, parents: usize) -> Option<usize> {
+        let mut cursors = self.fk_cursors.borrow_mut();
+        let remaining = cursors.entry(cursor.to_string()).or_insert_with(|| {
+            let mut order: Vec<usize> = (0..parents).collect();
+            order.shuffle(&mut self.rng());
+            order
+        });
+        remaining.pop()
+    }
+    
+    /// Writes a key for the column that receives it. Only numeric columns take a bare number;
+    /// uuid, text and anything else get a quoted literal, which Postgres casts to the column
+    /// type. Decided by the target's type alone, so a uuid stored as text still comes quoted.
+    fn key_literal(id: &str, dtype: &str) -> String {
+        let numeric = ["int", "serial", "numeric", "decimal", "real", "double"].iter().any(|t| dtype.contains(t));
+        if numeric && id.parse::<f64>().is_ok() {
+            id.to_string()
+        } else {
+            escape_sql_string(id)
+        }
+    }
+    
+    /// Self-references point at rows already generated for this table. Nullable ones leave
+    /// the first row (and a few others) as NULL roots; non-nullable roots point at themselves.
+    fn get_self_fk_value(&self, table: &str, col: &Column, ctx: &ContextEngine, row_idx: usize) -> String {
+        if let Some(shape) = self.options.tree {
+            return self.get_tree_parent(shape, table, col, ctx, row_idx);
+        }
+        let mut rng = self.rng();
+        let has_earlier = self.pk_storage.get(table).is_some_and(|ids| !ids.is_empty());
         
-        "NULL".to_string()
+        if col.is_nullable && (self.options.null_optional_fks || !has_earlier || rng.gen_bool(0.1)) {
+            return "NULL".to_string();
+        }
+        if let Some(id) = self.pk_storage.get(table).and_then(|ids| ids.choose(&mut rng)) {
+            return Self::key_literal(id, &col.data_type);
+        }
+        ctx.row_pk.clone().unwrap_or_else(|| self.generate_default(&col.data_type, row_idx))
+    }
+    
+    /// Whether a column is a self-reference whose NULLs `get_tree_parent` hands out itself, so
+    /// the usual null rate must not turn extra rows into roots.
+    fn shapes_tree(&self, semantic: &SemanticType, ctx: &ContextEngine) -> bool {
+        self.options.tree.is_some() && matches!(semantic, SemanticType::ForeignKey(ref_table) if *ref_table == ctx.table)
+    }
+    
+    /// `--tree-roots`/`--tree-max-depth`: the first rows are roots (NULL, or themselves when
+    /// the FK is NOT NULL), every later one hangs under a random earlier row that is still
+    /// above the depth limit.
+    fn get_tree_parent(&self, shape: TreeShape, table: &str, col: &Column, ctx: &ContextEngine, row_idx: usize) -> String {
+        let mut trees = self.trees.borrow_mut();
+        let tree = trees.entry(format!("{}.{}", table, col.name)).or_default();
+        let own = ctx.row_pk.as_deref().and_then(sql_literal_to_raw);
+        // A row drawn again (to satisfy a unique key) replaces its earlier registration.
+        if let Some(own) = own.as_ref().filter(|own| tree.levels.contains_key(*own)) {
+            tree.open.retain(|id| id != own);
+        }
+        let parent = if row_idx < shape.roots || self.options.null_optional_fks {
+            None
+        } else {
+            tree.open.choose(&mut self.rng()).cloned()
+        };
+        let level = parent.as_ref().map_or(1, |p| tree.levels.get(p).copied().unwrap_or(1) + 1);
+        if let Some(own) = own {
+            tree.levels.insert(own.clone(), level);
+            if shape.max_depth.is_none_or(|max| level < max) {
+                tree.open.push(own);
+            }
+        }
+        match parent {
+            Some(id) => Self::key_literal(&id, &col.data_type),
+            None if col.is_nullable => "NULL".to_string(),
+            None => ctx.row_pk.clone().unwrap_or_else(|| self.generate_default(&col.data_type, row_idx)),
+        }
     }
     
     fn generate_default(&self, dtype: &str, row_idx: usize) -> String {
         if dtype.contains("uuid") {
-            format!("'{}'", Uuid::new_v4())
+            escape_sql_string(&random_uuid(&mut self.rng()).to_string())
         } else if dtype.contains("int") {
             (row_idx + 1).to_string()
+        } else if dtype == "bytea" {
+            bytea_literal("")
         } else {
             "'default'".to_string()
         }
@@ -0,0 +1,15 @@
+//! SynthDB as a library: extract a Postgres schema, order it by FK dependencies, classify
+//! its columns into a [`generator::Plan`], and generate synthetic data to any writer.
+//!
+//! The `synthdb` binary is a thin CLI over these pieces:
+//! [`schema::extract_schema`] → [`sorter::sort_tables`] → [`generator::Plan::from_tables`]
+//! → [`generator::Generator::write_sql_dump`].
+
+#[macro_use]
+pub mod ui;
+pub mod schema;
+pub mod ddl;
+pub mod generator;
+pub mod sorter;
+pub mod sql;
+pub mod verify;
@@ -1,11 +1,13 @@
-mod schema;
-mod generator;
-mod sorter;
-
-use clap::{Parser, Subcommand};
-use sqlx::postgres::PgPoolOptions;
-use crate::generator::Generator;
-use std::time::Instant;
+use clap::{Args, Parser, Subcommand};
+use anyhow::{bail, Context};
+use sqlx::postgres::{PgConnectOptions, PgPool, PgPoolOptions};
+use std::env;
+use std::fs;
+use std::io::{BufReader, Read};
+use std::path::PathBuf;
+use synthdb::generator::{dump_path, load_fixtures, load_overrides, Compression, FkDistribution, GenerateOptions, Generator, Locale, Manifest, OnConflict, OutputFormat, Plan, Section, TreeShape};
+use synthdb::{schema, sorter, status, ui, verify};
+use std::time::{Duration, Instant};
 
 #[derive(Parser)]
 #[command(name = "synthdb")]
@@ -19,35 +21,310 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Clone a database structure and data patterns
-    Clone {
-        /// Postgres connection string
-        #[arg(short, long)]
-        url: String,
-
-        /// Output file path
-        #[arg(short, long, default_value = "seed.sql")]
-        output: String,
-
-        /// Rows to generate per table
-        #[arg(short, long, default_value = "1000")]
-        rows: usize,
-
-        /// Percentage of real data to sample (0-100)
-        #[arg(long, default_value = "20")]
-        sample_percent: u8,
-
-        /// Number of concurrent threads (placeholder for v0.2)
-        #[arg(long, default_value = "4")]
-        concurrency: usize,
-        
-        /// Target schema (placeholder for v0.2)
-        #[arg(long, default_value = "public")]
-        schema: String,
-
-        /// Dry run (analyze only, don't generate)
-        #[arg(long)]
-        dry_run: bool,
-    },
+    Clone(CloneArgs),
+}
+
+#[derive(Args)]
+struct CloneArgs {
+    /// Postgres connection string (falls back to DATABASE_URL; alternatively use --host/--dbname and friends)
+    #[arg(short, long, conflicts_with_all = ["host", "port", "user", "password", "dbname"])]
+    url: Option<String>,
+
+    /// Database host (falls back to PGHOST)
+    #[arg(long)]
+    host: Option<String>,
+
+    /// Database port (falls back to PGPORT, then 5432)
+    #[arg(long)]
+    port: Option<u16>,
+
+    /// Database user (falls back to PGUSER)
+    #[arg(long)]
+    user: Option<String>,
+
+    /// Database password (falls back to PGPASSWORD)
+    #[arg(long)]
+    password: Option<String>,
+
+    /// Database name (falls back to PGDATABASE)
+    #[arg(long)]
+    dbname: Option<String>,
+
+    /// Seconds to wait for each connection attempt before retrying
+    #[arg(long, value_name = "SECS", default_value = "30")]
+    connect_timeout: u64,
+
+    /// Size of the connection pool (lower it for databases with a tight connection cap)
+    #[arg(long, value_name = "N", default_value = "5", value_parser = clap::value_parser!(u32).range(1..))]
+    max_connections: u32,
+
+    /// Output file path (a directory when --format csv); `-` writes the SQL to stdout
+    #[arg(short, long, default_value = "seed.sql")]
+    output: String,
+
+    /// Output format
+    #[arg(long, value_enum, default_value_t = OutputFormat::Sql)]
+    format: OutputFormat,
+
+    /// Which part of the dump to write: synthetic rows, table DDL, or both. The DDL creates
+    /// enum types; domains and other user-defined types must already exist in the target
+    #[arg(long, value_enum, default_value_t = Section::Data)]
+    section: Section,
+
+    /// Rows to generate per table
+    #[arg(short, long, default_value = "1000")]
+    rows: usize,
+
+    /// Vary each table's size around --rows with this standard deviation, in percent of --rows
+    #[arg(long, value_name = "PCT", default_value = "0")]
+    rows_stddev: f64,
+
+    /// Start generated integer primary keys at N+1 (FKs follow), for disjoint ID ranges across runs
+    #[arg(long, value_name = "N", default_value = "0")]
+    pk_offset: u64,
+
+    /// Regional format for street addresses, postal codes and full-name order; places are set in the locale's country
+    #[arg(long, value_enum)]
+    locale: Option<Locale>,
+
+    /// Transliterate generated text to ASCII (output is UTF-8, accents included, by default)
+    #[arg(long)]
+    ascii_only: bool,
+
+    /// Skip integer primary key values now and then, as after deletions (chance per row, default 0.05)
+    #[arg(long, value_name = "PROB", num_args = 0..=1, default_missing_value = "0.05", value_parser = parse_fraction)]
+    pk_gaps: Option<f64>,
+
+    /// Compress the SQL dump (stdout included); files get a .gz/.zst extension appended
+    #[arg(long, value_enum, value_name = "CODEC")]
+    compress: Option<Compression>,
+
+    /// Stop adding rows once the SQL dump reaches this size (bytes, or with a K/M/G suffix); the dump stays valid SQL.
+    /// Tables are kept whole where possible: only a first table too large on its own is cut short
+    #[arg(long, value_name = "BYTES", value_parser = parse_size)]
+    max_size: Option<u64>,
+
+    /// Rows per INSERT statement; larger tables are split into several statements (0 = no limit)
+    #[arg(long, default_value = "1000")]
+    batch_size: usize,
+
+    /// Append ON CONFLICT on the primary key to each INSERT: skip existing rows or update them
+    #[arg(long, value_enum, value_name = "MODE")]
+    on_conflict: Option<OnConflict>,
+
+    /// Advance serial/identity primary key sequences past the generated keys after the data (`--reset-sequences false` to skip)
+    #[arg(long, value_name = "BOOL", default_value_t = true, action = clap::ArgAction::Set)]
+    reset_sequences: bool,
+
+    /// Emit TRUNCATE ... RESTART IDENTITY CASCADE for every table before the inserts
+    #[arg(long)]
+    truncate: bool,
+
+    /// Wrap each table's data in SAVEPOINT/RELEASE SAVEPOINT for manual recovery
+    #[arg(long, conflicts_with = "continue_on_error")]
+    savepoints: bool,
+
+    /// Commit each table in its own transaction so one failing table doesn't abort the rest
+    #[arg(long)]
+    continue_on_error: bool,
+
+    /// Leave out BEGIN/SET CONSTRAINTS/COMMIT so the loader can manage the transaction itself
+    #[arg(long, conflicts_with_all = ["continue_on_error", "savepoints"])]
+    no_transaction: bool,
+
+    /// Size each table as its source row count times this factor (e.g. 0.1 for a 10% copy)
+    #[arg(long, value_name = "FACTOR", value_parser = parse_scale)]
+    scale: Option<f64>,
+
+    /// Percentage of real data to sample (0-100)
+    #[arg(long, default_value = "20")]
+    sample_percent: u8,
+
+    /// Distinct values to sample per column
+    #[arg(long, value_name = "N", default_value = "20")]
+    sample_limit: usize,
+
+    /// Skip all data sampling and analyze from metadata alone (fast on huge schemas)
+    #[arg(long, conflicts_with = "sample_limit")]
+    no_sample: bool,
+
+    /// Only sample rows matching this SQL condition, in tables that have the columns it names
+    #[arg(long, value_name = "SQL", conflicts_with = "no_sample")]
+    sample_where: Option<String>,
+
+    /// Number of concurrent threads (placeholder for v0.2)
+    #[arg(long, default_value = "4")]
+    concurrency: usize,
+    
+    /// Generate only these tables, in exactly this order instead of the dependency order (comma-separated)
+    #[arg(long, value_name = "TABLE", value_delimiter = ',')]
+    tables: Vec<String>,
+
+    /// Source schema(s) to extract, comma-separated or repeated
+    #[arg(long = "db-schema", alias = "schema", value_name = "NAME", default_value = "public", value_delimiter = ',')]
+    db_schema: Vec<String>,
+
+    /// Suppress the progress bar and all status output (for scripts and CI)
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Write the analyzed model (tables, keys, semantic types, insertion order) as JSON
+    #[arg(long, value_name = "FILE")]
+    export_schema: Option<String>,
+
+    /// Write a provenance sidecar (version, timestamp, rows per table, source database name) as JSON
+    #[arg(long, value_name = "FILE")]
+    manifest: Option<String>,
+
+    /// Write per-column stats of the generated values (semantic type, NULLs, distinct count, numeric min/max) as JSON
+    #[arg(long, value_name = "FILE")]
+    stats: Option<String>,
+
+    /// Write the FK dependency graph in Graphviz DOT format
+    #[arg(long, value_name = "FILE")]
+    graphviz: Option<String>,
+
+    /// Add rows to the end of an existing dump, numbering primary keys on from the last ones in --manifest (which is updated)
+    #[arg(long, requires_all = ["plan_input", "manifest"])]
+    append: bool,
+
+    /// Generate from a previously exported (and possibly hand-edited) plan instead of analyzing a database
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["url", "host", "port", "user", "password", "dbname"])]
+    plan_input: Option<String>,
+
+    /// List the columns the classifier left on generic text/integer/decimal values
+    #[arg(long)]
+    report_unclassified: bool,
+
+    /// Dry run (analyze only, don't generate)
+    #[arg(long)]
+    dry_run: bool,
+
+    /// After writing the dump, replay it into a scratch `synthdb_verify` schema and report the first failing statement
+    #[arg(long, conflicts_with_all = ["plan_input", "dry_run"])]
+    verify: bool,
+
+    /// Size a table by a scalar SQL query, as `table=SELECT ...` (repeatable)
+    #[arg(long = "rows-from-query", value_name = "TABLE=SQL", value_parser = parse_table_query)]
+    rows_from_query: Vec<(String, String)>,
+
+    /// Also generate rows for updatable views (others, and materialized views, are listed as skipped)
+    #[arg(long)]
+    include_views: bool,
+
+    /// Treat undeclared `<table>_id` columns as FKs when a matching table exists
+    #[arg(long)]
+    infer_fks: bool,
+
+    /// Leave every nullable FK column NULL (required FKs still link to a parent)
+    #[arg(long)]
+    null_optional_fks: bool,
+
+    /// Abort when a NOT NULL foreign key has no parent row to reference instead of writing a placeholder value
+    #[arg(long)]
+    strict_fk: bool,
+
+    /// Self-referencing tables (e.g. categories.parent_id) form a forest with this many root rows (default 1 with --tree-max-depth)
+    #[arg(long, value_name = "N")]
+    tree_roots: Option<usize>,
+
+    /// Deepest level a self-referencing hierarchy reaches, counting the root as 1
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    tree_max_depth: Option<u64>,
+
+    /// How child rows pick the parent row they reference
+    #[arg(long, value_enum, default_value_t = FkDistribution::Uniform)]
+    fk_distribution: FkDistribution,
+
+    /// JSON file pinning columns: {"table.column": "constant:<value>" | "sequence:<start>" | "jsonschema:<path>" | "locale:<code>"}, `*.column` for all tables
+    #[arg(long, value_name = "FILE")]
+    overrides: Option<String>,
+
+    /// JSON or YAML file of literal rows for lookup tables: {"roles": [{"id": 1, "name": "admin"}]};
+    /// those tables get exactly these rows, other tables are generated as usual
+    #[arg(long, value_name = "FILE")]
+    fixtures: Option<String>,
+
+    /// Seed the random generator so a run can be repeated (timestamps still count back from the
+    /// current time); the seed used is recorded in the manifest
+    #[arg(long, value_name = "N")]
+    seed: Option<u64>,
+
+    /// Generate public, routable IPv4 addresses instead of private ranges (columns named internal/private excepted)
+    #[arg(long)]
+    public_ips: bool,
+
+    /// Leave a column out of the INSERTs so its default/NULL applies, as `table.column` (repeatable)
+    #[arg(long = "exclude-column", value_name = "TABLE.COLUMN")]
+    exclude_columns: Vec<String>,
+
+    /// Random bytes per generated bytea value
+    #[arg(long, value_name = "BYTES", default_value = "16")]
+    bytea_length: usize,
+
+    /// Child rows inherit city/state/country/coordinates from the parent row they reference
+    #[arg(long)]
+    inherit_location: bool,
+
+    /// Drop columns that are NULL in every generated row so their defaults apply
+    #[arg(long = "omit-null-columns-in-insert")]
+    omit_null_columns: bool,
+
+    /// NULL rate for nullable columns when the source NULL ratio couldn't be measured (0.0-1.0)
+    #[arg(long, default_value = "0.0", value_parser = parse_fraction)]
+    null_probability: f64,
+
+    /// Fill each nullable column for a random 20-90% of a table's rows, leaving the earliest
+    /// rows NULL like a feature rolled out partway through, instead of scattering NULLs per cell
+    #[arg(long)]
+    sparse_columns: bool,
+
+    /// Inject boundary values (empty/max-length strings, numeric extremes, tricky Unicode, date edges)
+    #[arg(long)]
+    fuzz_types: bool,
+
+    /// Fraction of rows that receive edge values when --fuzz-types is on (0.0-1.0)
+    #[arg(long, default_value = "0.05", value_parser = parse_fraction)]
+    fuzz_rate: f64,
+}
+
+fn parse_fraction(s: &str) -> Result<f64, String> {
+    let value: f64 = s.parse().map_err(|_| format!("'{}' is not a number", s))?;
+    if !(0.0..=1.0).contains(&value) {
+        return Err(format!("{} is not between 0.0 and 1.0", value));
+    }
+    Ok(value)
+}
+
+fn parse_scale(s: &str) -> Result<f64, String> {
+    let value: f64 = s.parse().map_err(|_| format!("'{}' is not a number", s))?;
+    if !value.is_finite() || value < 0.0 {
+        return Err(format!("{} is not a non-negative factor", value));
+    }
+    Ok(value)
+}
+
+fn parse_size(s: &str) -> Result<u64, String> {
+    let upper = s.trim().to_uppercase();
+    let digits = upper.trim_end_matches('B');
+    let (number, unit) = match digits.chars().last() {
+        Some('K') => (&digits[..digits.len() - 1], 1u64 << 10),
+        Some('M') => (&digits[..digits.len() - 1], 1 << 20),
+        Some('G') => (&digits[..digits.len() - 1], 1 << 30),
+        _ => (digits, 1),
+    };
+    let value: u64 = number.trim().parse().map_err(|_| format!("'{}' is not a size like 50000000, 500K or 50MB", s))?;
+    value.checked_mul(unit).ok_or_else(|| format!("'{}' is too large", s))
+}
+
+fn parse_table_query(s: &str) -> Result<(String, String), String> {
+    let (table, query) = s.split_once('=')
+        .ok_or_else(|| format!("expected TABLE=SQL, got '{}'", s))?;
+    let (table, query) = (table.trim(), query.trim());
+    if table.is_empty() || query.is_empty() {
+        return Err(format!("expected TABLE=SQL, got '{}'", s));
+    }
+    Ok((table.to_string(), query.to_string()))
 }
 
 #[tokio::main]
@@ -55,48 +332,361 @@ async fn main() -> anyhow::Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Clone { url, output, rows, sample_percent, concurrency: _, schema: _, dry_run } => {
-            let start = Instant::now();
-            
-            if dry_run {
-                println!("🐫 Dry run active. Analyzing schema only...");
-            } else {
-                println!("🚀 Connecting to database...");
+        Commands::Clone(args) => run_clone(args).await?,
+    }
+
+    Ok(())
+}
+
+/// Connects to the source database, extracts and sorts its schema, and classifies every column.
+async fn analyze_database(args: &CloneArgs, pool: &PgPool) -> anyhow::Result<Plan> {
+    if args.no_sample {
+        status!("🔍 Analyzing schema (sampling disabled)...");
+    } else {
+        status!("🔍 Analyzing schema & sampling data ({}%)...", args.sample_percent);
+    }
+    // Note: We are passing sample_percent to schema extractor now
+    // (You'll need to update extract_schema signature if you want this to actually limit the query)
+    let extract_options = schema::ExtractOptions {
+        schemas: args.db_schema.clone(),
+        row_queries: args.rows_from_query.iter().cloned().collect(),
+        include_views: args.include_views,
+        sample_limit: if args.no_sample { 0 } else { args.sample_limit },
+        sample_where: args.sample_where.clone(),
+    };
+    let mut raw_schema = schema::extract_schema(pool, &extract_options).await?;
+    if args.infer_fks {
+        let count = schema::infer_foreign_keys(&mut raw_schema);
+        status!("🔗 Inferred {} foreign key(s) from column names", count);
+    }
+    
+    status!("✅ Found {} tables. Calculating dependencies...", raw_schema.len());
+
+    // 2. Topological Sort (--tables fixes the order itself)
+    let sorted_schema = if args.tables.is_empty() {
+        let sorted = sorter::sort_tables(raw_schema)?;
+        status!("✅ Dependencies resolved. Insertion order determined.");
+        sorted
+    } else {
+        raw_schema
+    };
+    sorter::report_components(&sorted_schema);
+
+    let mut plan = Plan::from_tables(&sorted_schema);
+    plan.source_database = sqlx::query_scalar("SELECT current_database()::text").fetch_one(pool).await.ok();
+    Ok(plan)
+}
+
+/// Lists the columns that only got generic values, or just counts them unless `list` is set.
+fn report_unclassified(columns: &[(String, String, String)], list: bool) {
+    if columns.is_empty() {
+        return;
+    }
+    if !list {
+        status!("ℹ️ {} column(s) fell back to generic values; --report-unclassified lists them", columns.len());
+        return;
+    }
+    status!("ℹ️ {} column(s) fell back to generic values (candidates for --overrides):", columns.len());
+    for (table, column, data_type) in columns {
+        status!("   {}.{} ({})", table, column, data_type);
+    }
+}
+
+/// Reads a written dump back as text, decompressing it if it was written with `--compress`.
+fn read_dump(path: &str, compress: Option<Compression>) -> anyhow::Result<String> {
+    let file = fs::File::open(path)?;
+    let mut dump = String::new();
+    match compress {
+        None => BufReader::new(file).read_to_string(&mut dump)?,
+        Some(Compression::Gzip) => flate2::read::MultiGzDecoder::new(file).read_to_string(&mut dump)?,
+        Some(Compression::Zstd) => zstd::Decoder::new(file)?.read_to_string(&mut dump)?,
+    };
+    Ok(dump)
+}
+
+/// Attempts before giving up on the initial connection; waits double after each failure.
+const CONNECT_ATTEMPTS: u32 = 5;
+
+/// Opens the pool, retrying with exponential backoff so a database that is still starting
+/// (e.g. a CI container) gets a chance to come up. Each attempt is bounded by `timeout`.
+async fn connect_with_retry(options: PgConnectOptions, max_connections: u32, timeout: Duration) -> anyhow::Result<PgPool> {
+    let mut backoff = Duration::from_secs(1);
+    for attempt in 1..=CONNECT_ATTEMPTS {
+        let result = PgPoolOptions::new()
+            .max_connections(max_connections)
+            .acquire_timeout(timeout)
+            .connect_with(options.clone())
+            .await;
+        match result {
+            Ok(pool) => return Ok(pool),
+            Err(e) if attempt < CONNECT_ATTEMPTS => {
+                status!("⚠️ Connection attempt {}/{} failed ({}); retrying in {}s...", attempt, CONNECT_ATTEMPTS, e, backoff.as_secs());
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            Err(e) => {
+                return Err(e).with_context(|| format!("could not connect to the database after {} attempts", CONNECT_ATTEMPTS));
+            }
+        }
+    }
+    unreachable!("the last attempt always returns")
+}
+
+/// Builds connection options from `--url` (or `DATABASE_URL` when no connection flag is given),
+/// or from the individual flags with libpq-style environment and `~/.pgpass` fallbacks.
+fn connect_options(args: &CloneArgs) -> anyhow::Result<PgConnectOptions> {
+    let flags_given = args.host.is_some() || args.port.is_some() || args.user.is_some()
+        || args.password.is_some() || args.dbname.is_some();
+    let url = args.url.clone().or_else(|| if flags_given { None } else { env::var("DATABASE_URL").ok() });
+    if let Some(url) = url {
+        // sqlx consults ~/.pgpass itself when the URL carries no password.
+        return Ok(url.parse()?);
+    }
+
+    let host = args.host.clone().or_else(|| env::var("PGHOST").ok());
+    let dbname = args.dbname.clone().or_else(|| env::var("PGDATABASE").ok());
+    let (Some(host), Some(dbname)) = (host, dbname) else {
+        bail!("No database to connect to: pass --url or set DATABASE_URL, or give at least --host and --dbname (or set PGHOST/PGDATABASE)");
+    };
+
+    let mut options = PgConnectOptions::new_without_pgpass().host(&host).database(&dbname);
+    if let Some(port) = args.port {
+        options = options.port(port);
+    }
+    if let Some(user) = &args.user {
+        options = options.username(user);
+    }
+    let password = args.password.clone()
+        .or_else(|| env::var("PGPASSWORD").ok())
+        .or_else(|| pgpass_password(options.get_host(), options.get_port(), &dbname, options.get_username()));
+    if let Some(password) = password {
+        options = options.password(&password);
+    }
+    Ok(options)
+}
+
+/// Looks the password up in the libpq password file (`$PGPASSFILE`, else `~/.pgpass`): lines of
+/// `host:port:database:username:password`, where `*` matches anything and `\` escapes `:` or `\`.
+fn pgpass_password(host: &str, port: u16, dbname: &str, user: &str) -> Option<String> {
+    let path = env::var_os("PGPASSFILE").map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".pgpass")))?;
+    let contents = fs::read_to_string(path).ok()?;
+    let port = port.to_string();
+    let wanted = [host, port.as_str(), dbname, user];
+    contents.lines()
+        .filter(|line| !line.trim_start().starts_with('#'))
+        .map(split_pgpass_line)
+        .find(|fields| fields.len() == 5 && fields[..4].iter().zip(wanted).all(|(field, want)| field == "*" || field == want))
+        .map(|mut fields| fields.remove(4))
+}
+
+fn split_pgpass_line(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => fields.last_mut().unwrap().extend(chars.next()),
+            ':' if fields.len() < 5 => fields.push(String::new()),
+            _ => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
+
+async fn run_clone(args: CloneArgs) -> anyhow::Result<()> {
+    let start = Instant::now();
+    ui::set_quiet(args.quiet);
+    ui::set_stderr(args.output == "-");
+    
+    if args.dry_run {
+        status!("🐫 Dry run active. Analyzing schema only...");
+    }
+
+    // Read the overrides up front so a bad file fails before the database work.
+    let overrides = match &args.overrides {
+        Some(path) => load_overrides(path)?,
+        None => Default::default(),
+    };
+    let fixtures = match &args.fixtures {
+        Some(path) => load_fixtures(path)?,
+        None => Default::default(),
+    };
+
+    if args.verify && (args.output == "-" || args.format == OutputFormat::Csv) {
+        bail!("--verify replays a SQL dump file; it can't be combined with --output - or --format csv");
+    }
+
+    if args.max_size.is_some() && args.format == OutputFormat::Csv {
+        bail!("--max-size bounds the SQL dump; it isn't available with --format csv");
+    }
+    if args.compress.is_some() && args.format == OutputFormat::Csv {
+        bail!("--compress applies to the SQL dump; it isn't available with --format csv");
+    }
+    if args.append && (args.output == "-" || args.format == OutputFormat::Csv) {
+        bail!("--append extends a SQL dump file; it can't be combined with --output - or --format csv");
+    }
+    if args.append && (args.truncate || args.section != Section::Data) {
+        bail!("--append only adds rows; it can't be combined with --truncate or --section schema/all");
+    }
+
+    // The run being appended to says where each table's primary keys stopped.
+    let previous_run = match args.manifest.as_ref().filter(|_| args.append) {
+        Some(path) => {
+            let manifest = Manifest::read_json(path)?;
+            if manifest.last_keys.is_empty() {
+                status!("⚠️ {} records no primary keys; appended keys start over at --pk-offset", path);
+            }
+            Some(manifest)
+        }
+        None => None,
+    };
+
+    let (mut plan, pool) = match &args.plan_input {
+        Some(path) => {
+            status!("📂 Loading analysis plan from {} (skipping database analysis)...", path);
+            (Plan::read_json(path)?, None)
+        }
+        None => {
+            if !args.dry_run {
+                status!("🚀 Connecting to database...");
+            }
+            let pool = connect_with_retry(connect_options(&args)?, args.max_connections, Duration::from_secs(args.connect_timeout)).await?;
+            (analyze_database(&args, &pool).await?, Some(pool))
+        }
+    };
+
+    if !args.tables.is_empty() {
+        plan.select_tables(&args.tables)?;
+        status!("📌 Generating {} table(s) in the order given by --tables", plan.tables.len());
+    }
+
+    if let Some(factor) = args.scale {
+        // Explicit --rows-from-query counts win; tables without an estimate keep --rows.
+        for planned in &mut plan.tables {
+            let table = &mut planned.table;
+            if let (None, Some(estimate)) = (table.row_count, table.estimated_rows) {
+                table.row_count = Some((estimate as f64 * factor).ceil() as usize);
             }
+        }
+    }
+
+    // Fixture tables get exactly their fixture rows, whatever --rows or --scale said.
+    let fixtures = plan.apply_fixtures(fixtures)?;
+
+    for key in &args.exclude_columns {
+        let matched = plan.tables.iter().any(|planned| {
+            let table = &planned.table;
+            table.columns.iter().any(|c| {
+                [format!("{}.{}", table.qualified_name(), c.name), format!("{}.{}", table.table_name, c.name), format!("*.{}", c.name)]
+                    .contains(key)
+            })
+        });
+        if !matched {
+            status!("⚠️ Warning: --exclude-column '{}' matches no column", key);
+        }
+    }
 
-            let pool = PgPoolOptions::new()
-                .max_connections(5)
-                .connect(&url)
-                .await?;
-
-            println!("🔍 Analyzing schema & sampling data ({}%)...", sample_percent);
-            // Note: We are passing sample_percent to schema extractor now
-            // (You'll need to update extract_schema signature if you want this to actually limit the query)
-            let raw_schema = schema::extract_schema(&pool).await?;
-            
-            println!("✅ Found {} tables. Calculating dependencies...", raw_schema.len());
-
-            // 2. Topological Sort
-            let sorted_schema = sorter::sort_tables(raw_schema)?;
-            println!("✅ Dependencies resolved. Insertion order determined.");
-
-            if dry_run {
-                println!("📋 Execution Plan (Dry Run):");
-                for (i, table) in sorted_schema.iter().enumerate() {
-                    println!("  {}. {}", i + 1, table.table_name);
-                }
-                println!("✨ Dry run complete.");
-                return Ok(());
+    if let Some(path) = &args.export_schema {
+        plan.write_json(path)?;
+        status!("📝 Analyzed schema exported to {}", path);
+    }
+
+    if let Some(path) = &args.graphviz {
+        let tables: Vec<_> = plan.tables.iter().map(|t| t.table.clone()).collect();
+        sorter::write_graphviz(&tables, path)?;
+        status!("📝 Dependency graph written to {}", path);
+    }
+
+    let unclassified = plan.unclassified_columns();
+
+    if args.dry_run {
+        status!("📋 Execution Plan (Dry Run):");
+        for (i, planned) in plan.tables.iter().enumerate() {
+            let table = &planned.table;
+            match table.row_count {
+                Some(n) => status!("  {}. {} ({} rows)", i + 1, table.qualified_name(), n),
+                None => status!("  {}. {}", i + 1, table.qualified_name()),
             }
+        }
+        report_unclassified(&unclassified, args.report_unclassified);
+        status!("✨ Dry run complete.");
+        return Ok(());
+    }
 
-            println!("🔨 Generating synthetic data...");
-            // 3. Generate
-            let mut generator = Generator::new(sorted_schema);
-            generator.generate_sql_dump(&output, rows)?;
+    status!("🔨 Generating synthetic data...");
+    // 3. Generate
+    let options = GenerateOptions {
+        inherit_location: args.inherit_location,
+        omit_null_columns: args.omit_null_columns,
+        fuzz_rate: if args.fuzz_types { args.fuzz_rate } else { 0.0 },
+        format: args.format,
+        null_probability: args.null_probability,
+        sparse_columns: args.sparse_columns,
+        section: args.section,
+        fk_distribution: args.fk_distribution,
+        batch_size: args.batch_size,
+        savepoints: args.savepoints,
+        continue_on_error: args.continue_on_error,
+        no_transaction: args.no_transaction,
+        truncate: args.truncate,
+        overrides,
+        fixtures,
+        on_conflict: args.on_conflict,
+        public_ips: args.public_ips,
+        excluded_columns: args.exclude_columns.iter().cloned().collect(),
+        bytea_length: args.bytea_length,
+        pk_offset: args.pk_offset,
+        pk_offsets: previous_run.as_ref().map(|m| m.last_keys.clone().into_iter().collect()).unwrap_or_default(),
+        append: args.append,
+        rows_stddev: args.rows_stddev,
+        pk_gap_rate: args.pk_gaps.unwrap_or(0.0),
+        ascii_only: args.ascii_only,
+        null_optional_fks: args.null_optional_fks,
+        max_size: args.max_size,
+        strict_fk: args.strict_fk,
+        compress: args.compress,
+        locale: args.locale,
+        keep_order: !args.tables.is_empty(),
+        collect_stats: args.stats.is_some(),
+        reset_sequences: args.reset_sequences,
+        tree: (args.tree_roots.is_some() || args.tree_max_depth.is_some()).then(|| TreeShape {
+            roots: args.tree_roots.unwrap_or(1),
+            max_depth: args.tree_max_depth.map(|depth| depth as usize),
+        }),
+        seed: args.seed,
+    };
+    // The generator consumes the plan; --verify still needs the tables afterwards.
+    let verify_tables: Vec<_> = if args.verify { plan.tables.iter().map(|t| t.table.clone()).collect() } else { Vec::new() };
+    let mut generator = Generator::new(plan, options);
+    generator.generate_sql_dump(&args.output, args.rows)?;
+    let output = dump_path(&args.output, args.compress);
 
-            println!("✨ Done in {:.2?}! Saved to {}", start.elapsed(), output);
+    if let Some(pool) = pool.as_ref().filter(|_| args.verify) {
+        status!("🧪 Verifying dump against a scratch schema...");
+        let dump = read_dump(&output, args.compress).with_context(|| format!("could not read {} back for --verify", output))?;
+        let applied = verify::verify_dump(pool, &verify_tables, &dump, args.section == Section::Data)
+            .await
+            .context("--verify: the dump does not apply cleanly")?;
+        status!("✅ Verified: all {} statements applied cleanly", applied);
+    }
+
+    if let Some(path) = &args.manifest {
+        let mut manifest = generator.manifest();
+        if let Some(previous) = previous_run {
+            manifest.continue_from(previous);
         }
+        manifest.write_json(path)?;
+        status!("🧾 Manifest written to {}", path);
+    }
+
+    if let Some(path) = &args.stats {
+        generator.stats().write_json(path)?;
+        status!("📈 Value stats written to {}", path);
     }
 
+    report_unclassified(&unclassified, args.report_unclassified);
+    let destination = if args.output == "-" { "stdout" } else { output.as_str() };
+    status!("✨ Done in {:.2?}! Saved to {}", start.elapsed(), destination);
+
     Ok(())
 }
\ No newline at end of file
@@ -1,55 +1,252 @@
 use serde::{Deserialize, Serialize};
 use sqlx::postgres::PgPool;
 use sqlx::Row;
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use futures::stream::{self, StreamExt};
+use std::collections::HashMap;
+use crate::sql::{quote_ident, quote_table};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Column {
     pub name: String,
     pub data_type: String, // "integer", "text", "numeric", "ARRAY"
+    #[serde(default)]
+    pub sql_type: String,  // Full declared type, e.g. "character varying(20)", "integer[]"
+    pub column_default: Option<String>,
     pub is_nullable: bool,
+    #[serde(default)]
+    pub is_generated: bool,             // GENERATED ALWAYS (identity or stored expression): never inserted
+    pub identity_generation: Option<String>,   // "ALWAYS" / "BY DEFAULT" for identity columns
+    pub generation_expression: Option<String>, // Expression of a stored generated column
     pub numeric_precision: Option<i32>, // Total digits
     pub numeric_scale: Option<i32>,     // Decimal places
+    pub max_length: Option<i32>,        // varchar(n) / char(n) limit
     pub distinct_values: Vec<String>,   // Sampled data
+    pub observed_null_ratio: Option<f64>, // Share of NULLs in the source, if measured
+    #[serde(default)]
+    pub len_stats: Option<(usize, usize, usize)>, // (min, avg, max) character length of text values
+    #[serde(default)]
+    pub comment: Option<String>, // COMMENT ON COLUMN text, read as a classification hint
+    #[serde(default)]
+    pub email_domains: Vec<(String, f64)>, // Domains seen in an email column, with their share of its values
+    #[serde(default)]
+    pub enum_labels: Vec<String>, // Labels of a user-defined enum type (the element's, for enum[]), in sort order
+    #[serde(default)]
+    pub true_ratio: Option<f64>, // Share of non-NULL values that are true, for boolean columns
+    #[serde(default)]
+    pub datetime_precision: Option<i32>, // Fractional second digits of a timestamp/time type (0-6)
+}
+
+impl Column {
+    /// For an `ARRAY` column, a column describing one element: same name, the element type
+    /// from `sql_type` (`numeric(10,2)[]` -> `numeric` with precision 10, scale 2).
+    pub fn array_element(&self) -> Option<Column> {
+        if self.data_type != "ARRAY" {
+            return None;
+        }
+        let element_type = self.sql_type.trim_end_matches("[]").to_string();
+        let (data_type, modifiers) = match element_type.split_once('(') {
+            Some((base, rest)) => {
+                let (args, tail) = rest.split_once(')').unwrap_or((rest, ""));
+                (format!("{}{}", base.trim_end(), tail), args.split(',').filter_map(|a| a.trim().parse::<i32>().ok()).collect())
+            }
+            None => (element_type.clone(), Vec::new()),
+        };
+        let is_text = data_type.contains("char");
+        let is_numeric = data_type == "numeric";
+        let is_timestamp = data_type.starts_with("timestamp");
+        Some(Column {
+            sql_type: element_type,
+            numeric_precision: if is_numeric { modifiers.first().copied() } else { None },
+            numeric_scale: if is_numeric { modifiers.get(1).copied().or(modifiers.first().map(|_| 0)) } else { None },
+            max_length: if is_text { modifiers.first().copied() } else { None },
+            // Without a modifier a timestamp keeps microseconds.
+            datetime_precision: if is_timestamp { modifiers.first().copied().or(Some(6)) } else { None },
+            data_type,
+            distinct_values: Vec::new(),
+            len_stats: None,
+            ..self.clone()
+        })
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ForeignKey {
     pub column: String,
+    pub ref_schema: String,
     pub ref_table: String,
     pub ref_column: String,
+    #[serde(default)]
+    pub deferred: bool, // Breaks an FK cycle: inserted as a placeholder, then back-patched by UPDATE
+    #[serde(default)]
+    pub inferred: bool, // Guessed from the column name (--infer-fks); no constraint exists
+    #[serde(default)]
+    pub deferrable: bool, // Declared DEFERRABLE, so SET CONSTRAINTS can postpone its check to COMMIT
+    #[serde(default)]
+    pub on_delete: Option<String>, // Referential action: "CASCADE", "SET NULL", "SET DEFAULT", "RESTRICT", "NO ACTION"
+    #[serde(default)]
+    pub on_update: Option<String>, // Same actions, for updates of the referenced key
+}
+
+impl ForeignKey {
+    /// `schema.table` of the referenced table, matching `Table::qualified_name`.
+    pub fn ref_qualified_name(&self) -> String {
+        format!("{}.{}", self.ref_schema, self.ref_table)
+    }
+
+    /// Children are deleted along with their parent, so none exist on their own.
+    pub fn cascades(&self) -> bool {
+        self.on_delete.as_deref() == Some("CASCADE")
+    }
+
+    /// Children outlive their parent with the FK cleared, so some unlinked ones are expected.
+    pub fn sets_null(&self) -> bool {
+        self.on_delete.as_deref() == Some("SET NULL")
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Table {
+    pub schema_name: String,
     pub table_name: String,
     pub columns: Vec<Column>,
     pub foreign_keys: Vec<ForeignKey>,
+    #[serde(default)]
+    pub primary_keys: Vec<String>,
+    #[serde(default)]
+    pub unique_keys: Vec<Vec<String>>, // Column lists of UNIQUE constraints/indexes, composite ones included
+    #[serde(default)]
+    pub exclusion_constraints: Vec<Vec<(String, String)>>, // (column, operator) pairs of EXCLUDE constraints
+    #[serde(default)]
+    pub row_count: Option<usize>, // Overrides the global --rows when set
+    #[serde(default)]
+    pub estimated_rows: Option<usize>, // Source size from planner statistics (or count(*))
+    #[serde(default)]
+    pub is_view: bool, // An updatable view (--include-views): INSERTs only, no DDL or TRUNCATE
+}
+
+impl Table {
+    /// `schema.table`, unique across every extracted schema.
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}", self.schema_name, self.table_name)
+    }
+}
+
+/// Knobs that shape what `extract_schema` pulls from the source database.
+#[derive(Debug, Clone, Default)]
+pub struct ExtractOptions {
+    /// Source schemas to read; their tables are merged before sorting.
+    pub schemas: Vec<String>,
+    /// Per-table scalar queries whose result becomes that table's row count.
+    pub row_queries: HashMap<String, String>,
+    /// Also generate rows for views Postgres can insert into (simple updatable views).
+    pub include_views: bool,
+    /// Distinct values sampled per column. 0 skips every data query (distinct values, NULL
+    /// ratios, text lengths), leaving generation to name/type heuristics.
+    pub sample_limit: usize,
+    /// SQL condition restricting the sampled rows, applied to each table that has the
+    /// columns it names.
+    pub sample_where: Option<String>,
 }
 
-pub async fn extract_schema(pool: &PgPool) -> Result<Vec<Table>> {
-    // 1. Get all tables
-    let tables = sqlx::query!(
-        "SELECT table_name FROM information_schema.tables 
-         WHERE table_schema = 'public' AND table_type = 'BASE TABLE'"
+pub async fn extract_schema(pool: &PgPool, options: &ExtractOptions) -> Result<Vec<Table>> {
+    let schemas: Vec<String> = if options.schemas.is_empty() {
+        vec!["public".to_string()]
+    } else {
+        options.schemas.clone()
+    };
+
+    // A condition that doesn't even parse would fail on every table; warn about it once.
+    let mut sample_where = options.sample_where.as_deref();
+    if let Some(condition) = sample_where {
+        let probe = format!("SELECT 1 FROM (SELECT 1) AS probe WHERE ({}) LIMIT 0", condition);
+        if let Err(sqlx::Error::Database(e)) = sqlx::query(&probe).fetch_all(pool).await {
+            if e.code().as_deref() == Some(SYNTAX_ERROR) {
+                status!("⚠️ --sample-where is not a valid SQL condition ({}); sampling all rows", e);
+                sample_where = None;
+            }
+        }
+    }
+
+    // 1. Get all tables (and views, which are kept only when updatable and asked for)
+    let relations = sqlx::query!(
+        "SELECT table_schema, table_name, table_type, is_insertable_into FROM information_schema.tables 
+         WHERE table_schema = ANY($1) AND table_type IN ('BASE TABLE', 'VIEW')
+         ORDER BY table_schema, table_name",
+        &schemas
+    )
+    .fetch_all(pool)
+    .await?;
+
+    // Partitions are listed as tables of their own, but rows inserted into the partitioned
+    // parent are routed to them; generating both would insert everything twice.
+    let partitions: Vec<String> = sqlx::query_scalar!(
+        r#"SELECT n.nspname || '.' || c.relname AS "name!"
+           FROM pg_catalog.pg_class c
+           JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+           WHERE c.relispartition AND c.relkind IN ('r', 'p', 'f') AND n.nspname = ANY($1)
+           ORDER BY 1"#,
+        &schemas
+    )
+    .fetch_all(pool)
+    .await?;
+    if !partitions.is_empty() {
+        status!("⚠️ Skipping {} partition(s); rows go into their partitioned parent table: {}", partitions.len(), partitions.join(", "));
+    }
+
+    let mut tables = Vec::new();
+    let mut skipped = Vec::new();
+    for r in relations {
+        let (s_name, t_name) = (r.table_schema.unwrap(), r.table_name.unwrap());
+        if partitions.contains(&format!("{}.{}", s_name, t_name)) {
+            continue;
+        }
+        let is_view = r.table_type.as_deref() == Some("VIEW");
+        if is_view && !(options.include_views && r.is_insertable_into.as_deref() == Some("YES")) {
+            skipped.push(format!("{}.{}", s_name, t_name));
+        } else {
+            tables.push((s_name, t_name, is_view));
+        }
+    }
+
+    // Materialized views can't take INSERTs at all; they only show up in the skip list.
+    let matviews = sqlx::query!(
+        "SELECT schemaname, matviewname FROM pg_catalog.pg_matviews WHERE schemaname = ANY($1) ORDER BY 1, 2",
+        &schemas
     )
     .fetch_all(pool)
     .await?;
+    skipped.extend(matviews.into_iter().map(|m| format!("{}.{} (materialized)", m.schemaname.unwrap_or_default(), m.matviewname.unwrap_or_default())));
+    if !skipped.is_empty() {
+        let hint = if options.include_views { "" } else { "; --include-views adds updatable views" };
+        status!("⚠️ Skipping {} view(s) that won't be populated{}: {}", skipped.len(), hint, skipped.join(", "));
+    }
 
     let mut schema = Vec::new();
 
-    for t in tables {
-        let t_name = t.table_name.unwrap();
-        println!("   ...analyzing table: {}", t_name);
+    for (s_name, t_name, is_view) in tables {
+        status!("   ...analyzing table: {}.{}", s_name, t_name);
 
         // 2. Get columns with precision details
         // We look at udt_name to detect Arrays (usually starts with _)
         let cols_raw = sqlx::query!(
-            "SELECT column_name, data_type, is_nullable, numeric_precision, numeric_scale, udt_name
-             FROM information_schema.columns 
-             WHERE table_name = $1 AND table_schema = 'public'
-             ORDER BY ordinal_position",
-            t_name
+            "SELECT c.column_name, c.data_type, c.is_nullable, c.numeric_precision, c.numeric_scale,
+                    c.character_maximum_length, c.datetime_precision, c.udt_name, c.column_default,
+                    c.is_generated, c.generation_expression, c.identity_generation,
+                    format_type(a.atttypid, a.atttypmod) AS sql_type,
+                    col_description(a.attrelid, a.attnum) AS comment,
+                    ARRAY(SELECT e.enumlabel::text FROM pg_catalog.pg_enum e
+                          WHERE e.enumtypid = COALESCE(NULLIF(
+                              (SELECT t.typelem FROM pg_catalog.pg_type t WHERE t.oid = a.atttypid), 0), a.atttypid)
+                          ORDER BY e.enumsortorder) AS enum_labels
+             FROM information_schema.columns c
+             JOIN pg_catalog.pg_attribute a
+               ON a.attrelid = (quote_ident(c.table_schema) || '.' || quote_ident(c.table_name))::regclass
+              AND a.attname = c.column_name
+             WHERE c.table_name = $1 AND c.table_schema = $2
+             ORDER BY c.ordinal_position",
+            t_name,
+            s_name
         )
         .fetch_all(pool)
         .await?;
@@ -65,73 +262,512 @@ pub async fn extract_schema(pool: &PgPool) -> Result<Vec<Table>> {
             if udt_name.starts_with('_') {
                 data_type = "ARRAY".to_string();
             }
+            // hstore is an extension type, reported only as USER-DEFINED.
+            if udt_name == "hstore" {
+                data_type = "hstore".to_string();
+            }
 
             let is_nullable = c.is_nullable.unwrap() == "YES";
             let numeric_precision = c.numeric_precision;
             let numeric_scale = c.numeric_scale;
-
-            // 3. THE SAMPLER: Only sample if it makes sense
-            let mut distinct_values = Vec::new();
-            // Avoid sampling IDs or massive text fields
-            if (data_type == "text" || data_type.contains("char")) 
-                && !col_name.contains("id") 
-                && !col_name.contains("email") 
-                && !col_name.contains("name") 
-                && !col_name.contains("url") {
-                
-                let query = format!("SELECT DISTINCT {} FROM {} LIMIT 20", col_name, t_name);
-                if let Ok(rows) = sqlx::query(&query).fetch_all(pool).await {
-                    for row in rows {
-                        if let Ok(val) = row.try_get::<String, _>(0) {
-                            if !val.trim().is_empty() {
-                                distinct_values.push(val);
-                            }
-                        }
-                    }
-                }
-            }
+            let max_length = c.character_maximum_length;
+            let sql_type = c.sql_type.unwrap_or_else(|| data_type.clone());
+            let column_default = c.column_default;
+            let identity_generation = c.identity_generation;
+            let generation_expression = c.generation_expression;
+            let is_generated = c.is_generated.as_deref() == Some("ALWAYS")
+                || identity_generation.as_deref() == Some("ALWAYS");
 
             columns.push(Column {
                 name: col_name,
                 data_type,
+                sql_type,
+                column_default,
                 is_nullable,
+                is_generated,
+                identity_generation,
+                generation_expression,
                 numeric_precision,
                 numeric_scale,
-                distinct_values,
+                max_length,
+                distinct_values: Vec::new(),
+                observed_null_ratio: None,
+                len_stats: None,
+                comment: c.comment,
+                email_domains: Vec::new(),
+                enum_labels: c.enum_labels.unwrap_or_default(),
+                true_ratio: None,
+                datetime_precision: c.datetime_precision,
             });
         }
 
-        // 4. Get Foreign Keys
+        if options.sample_limit > 0 {
+            let filter = match sample_where {
+                Some(condition) => sample_filter(pool, &s_name, &t_name, condition).await,
+                None => String::new(),
+            };
+
+            // 3. Measure how often nullable columns are actually NULL
+            sample_null_ratios(pool, &s_name, &t_name, &filter, &mut columns).await;
+
+            // 3b. Measure text lengths so free text comes out realistically sized
+            sample_length_stats(pool, &s_name, &t_name, &filter, &mut columns).await;
+
+            // 3c. THE SAMPLER: distinct values of short text and integer columns
+            sample_distinct_values(pool, &s_name, &t_name, &filter, &mut columns, options.sample_limit).await;
+
+            // 3d. Which domains the email columns actually use
+            sample_email_domains(pool, &s_name, &t_name, &filter, &mut columns).await;
+
+            // 3e. How often boolean flags are actually set
+            sample_true_ratios(pool, &s_name, &t_name, &filter, &mut columns).await;
+        }
+
+        // 4. Get Primary Key columns
+        let primary_keys: Vec<String> = sqlx::query!(
+            r#"
+            SELECT kcu.column_name
+            FROM information_schema.table_constraints AS tc
+            JOIN information_schema.key_column_usage AS kcu
+            ON tc.constraint_name = kcu.constraint_name AND tc.constraint_schema = kcu.constraint_schema
+            WHERE tc.table_name = $1 AND tc.table_schema = $2 AND tc.constraint_type = 'PRIMARY KEY'
+            ORDER BY kcu.ordinal_position
+            "#,
+            t_name,
+            s_name
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .filter_map(|r| r.column_name)
+        .collect();
+
+        // 4b. Get UNIQUE constraints and plain unique indexes (partial and expression indexes skipped)
+        let unique_keys: Vec<Vec<String>> = sqlx::query!(
+            r#"
+            SELECT array_agg(a.attname::text ORDER BY k.ord) AS "columns!"
+            FROM pg_index i
+            JOIN pg_class c ON c.oid = i.indrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            CROSS JOIN LATERAL unnest(i.indkey::int2[]) WITH ORDINALITY AS k(attnum, ord)
+            JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum = k.attnum
+            WHERE c.relname = $1 AND n.nspname = $2
+              AND i.indisunique AND NOT i.indisprimary AND i.indpred IS NULL
+              AND NOT (0 = ANY(i.indkey::int2[])) AND k.ord <= i.indnkeyatts
+            GROUP BY i.indexrelid
+            "#,
+            t_name,
+            s_name
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|r| r.columns)
+        .collect();
+
+        // 4c. Get EXCLUDE constraints over plain columns (expression elements skipped)
+        let exclusion_constraints: Vec<Vec<(String, String)>> = sqlx::query!(
+            r#"
+            SELECT array_agg(a.attname::text ORDER BY k.ord) AS "columns!",
+                   array_agg(o.oprname::text ORDER BY k.ord) AS "operators!"
+            FROM pg_constraint con
+            JOIN pg_class c ON c.oid = con.conrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            CROSS JOIN LATERAL unnest(con.conkey, con.conexclop) WITH ORDINALITY AS k(attnum, opoid, ord)
+            JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum = k.attnum
+            JOIN pg_operator o ON o.oid = k.opoid
+            WHERE c.relname = $1 AND n.nspname = $2 AND con.contype = 'x'
+              AND NOT (0 = ANY(con.conkey))
+            GROUP BY con.oid
+            "#,
+            t_name,
+            s_name
+        )
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .map(|r| r.columns.into_iter().zip(r.operators).collect())
+        .collect();
+
+        // 5. Get Foreign Keys
         let fks = sqlx::query!(
             r#"
             SELECT
                 kcu.column_name,
+                ccu.table_schema AS foreign_table_schema,
                 ccu.table_name AS foreign_table_name,
-                ccu.column_name AS foreign_column_name
+                ccu.column_name AS foreign_column_name,
+                tc.is_deferrable,
+                rc.delete_rule,
+                rc.update_rule
             FROM information_schema.key_column_usage AS kcu
             JOIN information_schema.constraint_column_usage AS ccu
-            ON kcu.constraint_name = ccu.constraint_name
+            ON kcu.constraint_name = ccu.constraint_name AND kcu.constraint_schema = ccu.constraint_schema
             JOIN information_schema.table_constraints AS tc
-            ON kcu.constraint_name = tc.constraint_name
-            WHERE kcu.table_name = $1 AND tc.constraint_type = 'FOREIGN KEY'
+            ON kcu.constraint_name = tc.constraint_name AND kcu.constraint_schema = tc.constraint_schema
+            JOIN information_schema.referential_constraints AS rc
+            ON kcu.constraint_name = rc.constraint_name AND kcu.constraint_schema = rc.constraint_schema
+            WHERE kcu.table_name = $1 AND kcu.table_schema = $2 AND tc.constraint_type = 'FOREIGN KEY'
             "#,
-            t_name
+            t_name,
+            s_name
         )
         .fetch_all(pool)
         .await?;
 
         let foreign_keys: Vec<ForeignKey> = fks.into_iter().map(|f| ForeignKey {
             column: f.column_name.unwrap(),
+            ref_schema: f.foreign_table_schema.unwrap(),
             ref_table: f.foreign_table_name.unwrap(),
             ref_column: f.foreign_column_name.unwrap(),
+            deferred: false,
+            inferred: false,
+            deferrable: f.is_deferrable.as_deref() == Some("YES"),
+            on_delete: f.delete_rule,
+            on_update: f.update_rule,
         }).collect();
 
+        // 6. Estimate the source size, for --scale
+        let estimated_rows = estimate_row_count(pool, &s_name, &t_name).await;
+
+        // 7. Size the table from a user-supplied query, if any
+        let qualified = format!("{}.{}", s_name, t_name);
+        let row_query = options.row_queries.get(&qualified).or_else(|| options.row_queries.get(&t_name));
+        let row_count = match row_query {
+            Some(query) => Some(
+                run_row_count_query(pool, query)
+                    .await
+                    .with_context(|| format!("--rows-from-query failed for table '{}'", t_name))?,
+            ),
+            None => None,
+        };
+
         schema.push(Table {
+            schema_name: s_name,
             table_name: t_name,
             columns,
             foreign_keys,
+            primary_keys,
+            unique_keys,
+            exclusion_constraints,
+            row_count,
+            estimated_rows,
+            is_view,
         });
     }
 
+    for table in options.row_queries.keys() {
+        if !schema.iter().any(|t| &t.table_name == table || &t.qualified_name() == table) {
+            status!("⚠️ Warning: --rows-from-query given for unknown table '{}'", table);
+        }
+    }
+
     Ok(schema)
+}
+
+/// Adds soft FKs for undeclared `<name>_id` columns when a table called `<name>` (or a plural
+/// of it) has a single-column PK of a compatible type. Same-schema tables win. Returns how
+/// many were added.
+pub fn infer_foreign_keys(tables: &mut [Table]) -> usize {
+    let mut inferred = Vec::new();
+    for (t_idx, table) in tables.iter().enumerate() {
+        for col in &table.columns {
+            // `tag_ids integer[]` holds references to `tags` just like `tag_id` holds one.
+            let element = col.array_element();
+            let suffix = if element.is_some() { "_ids" } else { "_id" };
+            let Some(stem) = col.name.to_lowercase().strip_suffix(suffix).map(str::to_string) else { continue };
+            let data_type = element.map_or(col.data_type.clone(), |e| e.data_type);
+            if stem.is_empty() || table.foreign_keys.iter().any(|fk| fk.column == col.name) {
+                continue;
+            }
+
+            let candidates = [stem.clone(), format!("{}s", stem), format!("{}es", stem),
+                stem.strip_suffix('y').map(|s| format!("{}ies", s)).unwrap_or_default()];
+            let parent = tables.iter()
+                .filter(|p| candidates.contains(&p.table_name.to_lowercase()) && p.primary_keys.len() == 1)
+                .min_by_key(|p| p.schema_name != table.schema_name);
+            let Some(parent) = parent else { continue };
+            let Some(pk) = parent.columns.iter().find(|c| c.name == parent.primary_keys[0]) else { continue };
+
+            let compatible = pk.data_type == data_type
+                || (pk.data_type.contains("int") && data_type.contains("int"));
+            if compatible {
+                inferred.push((t_idx, ForeignKey {
+                    column: col.name.clone(),
+                    ref_schema: parent.schema_name.clone(),
+                    ref_table: parent.table_name.clone(),
+                    ref_column: pk.name.clone(),
+                    deferred: false,
+                    inferred: true,
+                    deferrable: false,
+                    on_delete: None,
+                    on_update: None,
+                }));
+            }
+        }
+    }
+
+    let count = inferred.len();
+    for (t_idx, fk) in inferred {
+        status!("   ...inferred FK {}.{} -> {}", tables[t_idx].table_name, fk.column, fk.ref_qualified_name());
+        tables[t_idx].foreign_keys.push(fk);
+    }
+    count
+}
+
+/// Text columns averaging more characters than this are documents, not categories: not sampled.
+const SAMPLE_MAX_AVG_LEN: usize = 100;
+
+/// Sampled values are cut to this many characters so one huge value can't bloat the plan.
+const SAMPLE_MAX_VALUE_LEN: usize = 200;
+
+/// Fills `distinct_values` with up to `limit` values per column, running one query per column
+/// concurrently (bounded by the pool size). The choice is by type and size rather than name:
+/// text columns whose average length (from `len_stats`) stays under `SAMPLE_MAX_AVG_LEN`, and
+/// small integers, for 0/1 flags, and decimals, so ratios can tell fractions from percents.
+/// Run after `sample_length_stats`.
+async fn sample_distinct_values(pool: &PgPool, schema: &str, table: &str, filter: &str, columns: &mut [Column], limit: usize) {
+    let queries: Vec<(usize, String)> = columns.iter()
+        .enumerate()
+        .filter(|(_, c)| {
+            let dtype = c.data_type.as_str();
+            let short_text = (dtype == "text" || dtype.contains("char"))
+                && c.len_stats.is_none_or(|(_, avg, _)| avg <= SAMPLE_MAX_AVG_LEN);
+            short_text || ["smallint", "integer", "numeric", "real", "double precision"].contains(&dtype)
+        })
+        .map(|(idx, c)| {
+            let query = format!(
+                "SELECT DISTINCT left({}::text, {}) FROM {}{} LIMIT {}",
+                quote_ident(&c.name),
+                SAMPLE_MAX_VALUE_LEN,
+                quote_table(schema, table),
+                filter,
+                limit
+            );
+            (idx, query)
+        })
+        .collect();
+
+    let concurrency = (pool.options().get_max_connections() as usize).max(1);
+    let samples: Vec<(usize, Vec<String>)> = stream::iter(queries)
+        .map(|(idx, query)| async move {
+            let values = match sqlx::query(&query).fetch_all(pool).await {
+                Ok(rows) => rows.iter()
+                    .filter_map(|row| row.try_get::<String, _>(0).ok())
+                    .filter(|val| !val.trim().is_empty())
+                    .collect(),
+                Err(_) => Vec::new(),
+            };
+            (idx, values)
+        })
+        .buffer_unordered(concurrency)
+        .collect()
+        .await;
+
+    // Results arrive in completion order; the index puts each back on its own column.
+    for (idx, values) in samples {
+        columns[idx].distinct_values = values;
+    }
+}
+
+/// The ` WHERE (...)` clause `--sample-where` adds to this table's sampling queries, found by
+/// planning the condition against the table. A table lacking a column the condition names is
+/// sampled in full without comment; any other error warns and falls back to full sampling.
+async fn sample_filter(pool: &PgPool, schema: &str, table: &str, condition: &str) -> String {
+    let probe = format!("SELECT 1 FROM {} WHERE ({}) LIMIT 0", quote_table(schema, table), condition);
+    match sqlx::query(&probe).fetch_all(pool).await {
+        Ok(_) => format!(" WHERE ({})", condition),
+        Err(sqlx::Error::Database(e)) if e.code().as_deref() == Some(UNDEFINED_COLUMN) => String::new(),
+        Err(e) => {
+            status!("⚠️ --sample-where can't filter {}.{} ({}); sampling all rows", schema, table, e);
+            String::new()
+        }
+    }
+}
+
+/// SQLSTATE for a reference to a column the table doesn't have.
+const UNDEFINED_COLUMN: &str = "42703";
+
+/// SQLSTATE for SQL that doesn't parse.
+const SYNTAX_ERROR: &str = "42601";
+
+/// Most common domains kept per email column; the long tail is dropped.
+const EMAIL_DOMAIN_LIMIT: usize = 20;
+
+/// Fills `email_domains` with the most common domains (lowercased, with their share of the
+/// addresses) of text columns named like an email or whose sampled values contain `@`.
+/// Run after `sample_distinct_values`.
+async fn sample_email_domains(pool: &PgPool, schema: &str, table: &str, filter: &str, columns: &mut [Column]) {
+    for col in columns.iter_mut() {
+        let text = col.data_type == "text" || col.data_type.contains("char");
+        let emails = col.name.to_lowercase().contains("mail") || col.distinct_values.iter().any(|v| v.contains('@'));
+        if !text || !emails {
+            continue;
+        }
+        let query = format!(
+            "SELECT lower(split_part(v, '@', 2)) AS domain, count(*) AS n
+             FROM (SELECT {}::text AS v FROM {}{}) s
+             WHERE v LIKE '%_@_%'
+             GROUP BY 1 ORDER BY 2 DESC LIMIT {}",
+            quote_ident(&col.name),
+            quote_table(schema, table),
+            filter,
+            EMAIL_DOMAIN_LIMIT
+        );
+        let Ok(rows) = sqlx::query(&query).fetch_all(pool).await else { continue };
+        let counts: Vec<(String, i64)> = rows.iter()
+            .filter_map(|row| Some((row.try_get::<String, _>(0).ok()?, row.try_get::<i64, _>(1).ok()?)))
+            .collect();
+        let total: i64 = counts.iter().map(|(_, n)| n).sum();
+        if total > 0 {
+            col.email_domains = counts.into_iter().map(|(domain, n)| (domain, n as f64 / total as f64)).collect();
+        }
+    }
+}
+
+/// Fills `observed_null_ratio` for nullable columns with one `count(col)` pass over the table.
+/// Failures and empty tables leave the ratios unset so the generator falls back to its default.
+async fn sample_null_ratios(pool: &PgPool, schema: &str, table: &str, filter: &str, columns: &mut [Column]) {
+    let nullable: Vec<usize> = columns.iter()
+        .enumerate()
+        .filter(|(_, c)| c.is_nullable)
+        .map(|(idx, _)| idx)
+        .collect();
+    if nullable.is_empty() {
+        return;
+    }
+
+    let counts: Vec<String> = nullable.iter()
+        .map(|&idx| format!("count({})", quote_ident(&columns[idx].name)))
+        .collect();
+    let query = format!("SELECT count(*), {} FROM {}{}", counts.join(", "), quote_table(schema, table), filter);
+
+    let Ok(row) = sqlx::query(&query).fetch_one(pool).await else {
+        return;
+    };
+    let total: i64 = row.try_get(0).unwrap_or(0);
+    if total == 0 {
+        return;
+    }
+    for (pos, &idx) in nullable.iter().enumerate() {
+        if let Ok(non_null) = row.try_get::<i64, _>(pos + 1) {
+            columns[idx].observed_null_ratio = Some((total - non_null) as f64 / total as f64);
+        }
+    }
+}
+
+/// Fills `true_ratio` for boolean columns with one `avg(col::int)` pass. Columns that are
+/// NULL throughout (or a failed query) keep `None`.
+async fn sample_true_ratios(pool: &PgPool, schema: &str, table: &str, filter: &str, columns: &mut [Column]) {
+    let flags: Vec<usize> = columns.iter()
+        .enumerate()
+        .filter(|(_, c)| c.data_type == "boolean")
+        .map(|(idx, _)| idx)
+        .collect();
+    if flags.is_empty() {
+        return;
+    }
+
+    let averages: Vec<String> = flags.iter()
+        .map(|&idx| format!("avg({}::int)::float8", quote_ident(&columns[idx].name)))
+        .collect();
+    let query = format!("SELECT {} FROM {}{}", averages.join(", "), quote_table(schema, table), filter);
+
+    let Ok(row) = sqlx::query(&query).fetch_one(pool).await else {
+        return;
+    };
+    for (pos, &idx) in flags.iter().enumerate() {
+        if let Ok(Some(ratio)) = row.try_get::<Option<f64>, _>(pos) {
+            columns[idx].true_ratio = Some(ratio);
+        }
+    }
+}
+
+/// Fills `len_stats` for text/varchar columns with one min/avg/max `char_length` pass.
+/// Columns with no non-NULL values (or a failed query) keep `None`.
+async fn sample_length_stats(pool: &PgPool, schema: &str, table: &str, filter: &str, columns: &mut [Column]) {
+    let text_columns: Vec<usize> = columns.iter()
+        .enumerate()
+        .filter(|(_, c)| c.data_type == "text" || c.data_type == "character varying")
+        .map(|(idx, _)| idx)
+        .collect();
+    if text_columns.is_empty() {
+        return;
+    }
+
+    let aggregates: Vec<String> = text_columns.iter()
+        .map(|&idx| {
+            let col = quote_ident(&columns[idx].name);
+            format!(
+                "min(char_length({c}))::int8, round(avg(char_length({c})))::int8, max(char_length({c}))::int8",
+                c = col
+            )
+        })
+        .collect();
+    let query = format!("SELECT {} FROM {}{}", aggregates.join(", "), quote_table(schema, table), filter);
+
+    let Ok(row) = sqlx::query(&query).fetch_one(pool).await else {
+        return;
+    };
+    for (pos, &idx) in text_columns.iter().enumerate() {
+        let stat = |offset: usize| row.try_get::<Option<i64>, _>(pos * 3 + offset).ok().flatten();
+        if let (Some(min), Some(avg), Some(max)) = (stat(0), stat(1), stat(2)) {
+            columns[idx].len_stats = Some((min as usize, avg as usize, max as usize));
+        }
+    }
+}
+
+/// Reads the planner's row estimate from `pg_class.reltuples`, counting the rows instead when
+/// the table has never been analyzed (reltuples is -1 on PG14+, 0 on older servers).
+async fn estimate_row_count(pool: &PgPool, schema: &str, table: &str) -> Option<usize> {
+    let reltuples = sqlx::query!(
+        "SELECT c.reltuples::float8 AS reltuples
+         FROM pg_catalog.pg_class c
+         JOIN pg_catalog.pg_namespace n ON n.oid = c.relnamespace
+         WHERE n.nspname = $1 AND c.relname = $2",
+        schema,
+        table
+    )
+    .fetch_optional(pool)
+    .await
+    .ok()
+    .flatten()
+    .and_then(|r| r.reltuples);
+
+    match reltuples {
+        Some(estimate) if estimate > 0.0 => Some(estimate.round() as usize),
+        _ => {
+            let query = format!("SELECT count(*) FROM {}", quote_table(schema, table));
+            let row = sqlx::query(&query).fetch_one(pool).await.ok()?;
+            row.try_get::<i64, _>(0).ok().map(|n| n as usize)
+        }
+    }
+}
+
+/// Runs a scalar row-count query inside a read-only transaction and validates the result.
+async fn run_row_count_query(pool: &PgPool, query: &str) -> Result<usize> {
+    let head = query.trim_start().to_lowercase();
+    if !head.starts_with("select") && !head.starts_with("with") {
+        bail!("query must be a SELECT statement");
+    }
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("SET TRANSACTION READ ONLY").execute(&mut *tx).await?;
+    let rows = sqlx::query(query).fetch_all(&mut *tx).await?;
+    tx.rollback().await?;
+
+    if rows.len() != 1 || rows[0].len() != 1 {
+        bail!("query must return exactly one row with one column");
+    }
+
+    let row = &rows[0];
+    let count = row.try_get::<i64, _>(0)
+        .or_else(|_| row.try_get::<i32, _>(0).map(i64::from))
+        .or_else(|_| row.try_get::<i16, _>(0).map(i64::from))
+        .context("query must return an integer")?;
+
+    if count < 0 {
+        bail!("query returned a negative row count ({})", count);
+    }
+    Ok(count as usize)
 }
\ No newline at end of file
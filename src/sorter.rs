@@ -1,49 +1,124 @@
 use crate::schema::Table;
 use petgraph::graph::{DiGraph, NodeIndex};
-use petgraph::algo::toposort;
-use std::collections::HashMap;
+use petgraph::algo::{connected_components, tarjan_scc, toposort};
+use petgraph::dot::Dot;
+use petgraph::visit::EdgeRef;
+use std::collections::{HashMap, HashSet};
+use std::fs;
 use anyhow::Result; // FIXED: Removed unused `anyhow` macro import
 
-pub fn sort_tables(tables: Vec<Table>) -> Result<Vec<Table>> {
-    let mut graph = DiGraph::<&Table, ()>::new();
+/// Builds the FK dependency graph with edges pointing Parent -> Child. Self-references and
+/// deferred FKs don't constrain ordering, so they're only kept when `all_edges` is set.
+fn dependency_graph(tables: &[Table], all_edges: bool) -> DiGraph<&Table, &str> {
+    let mut graph = DiGraph::<&Table, &str>::new();
     let mut indices: HashMap<String, NodeIndex> = HashMap::new();
 
     // 1. Create Nodes
-    for table in &tables {
+    for table in tables {
         let idx = graph.add_node(table);
-        indices.insert(table.table_name.clone(), idx);
+        indices.insert(table.qualified_name(), idx);
     }
 
     // 2. Create Edges (Dependencies)
-    for table in &tables {
-        if let Some(child_idx) = indices.get(&table.table_name) {
+    for table in tables {
+        if let Some(child_idx) = indices.get(&table.qualified_name()) {
             for fk in &table.foreign_keys {
-                if let Some(parent_idx) = indices.get(&fk.ref_table) {
+                if let Some(parent_idx) = indices.get(&fk.ref_qualified_name()) {
                     // Dependency: Parent -> Child (Parent must exist before Child)
-                    if child_idx != parent_idx {
-                        graph.add_edge(*parent_idx, *child_idx, ());
+                    if (child_idx != parent_idx && !fk.deferred) || all_edges {
+                        graph.add_edge(*parent_idx, *child_idx, fk.column.as_str());
                     }
                 }
             }
         }
     }
 
-    // 3. Perform Topological Sort
-    match toposort(&graph, None) {
-        Ok(sorted_indices) => {
-            let sorted_tables: Vec<Table> = sorted_indices
-                .iter()
-                .map(|idx| {
-                    let t = graph[*idx];
-                    t.clone()
-                })
-                .collect();
-            Ok(sorted_tables)
-        }
-        Err(cycle) => {
-            let node = graph[cycle.node_id()];
-            println!("⚠️ Warning: Circular dependency detected involving table '{}'. Falling back to standard order.", node.table_name);
-            Ok(tables)
+    graph
+}
+
+pub fn sort_tables(mut tables: Vec<Table>) -> Result<Vec<Table>> {
+    loop {
+        let graph = dependency_graph(&tables, false);
+
+        // 3. Perform Topological Sort
+        let cycle_edge = match toposort(&graph, None) {
+            Ok(sorted_indices) => {
+                let sorted_tables: Vec<Table> = sorted_indices
+                    .iter()
+                    .map(|idx| {
+                        let t = graph[*idx];
+                        t.clone()
+                    })
+                    .collect();
+                return Ok(sorted_tables);
+            }
+            Err(cycle) => match pick_cycle_edge(&graph) {
+                Some(edge) => edge,
+                None => {
+                    let node = graph[cycle.node_id()];
+                    status!("⚠️ Warning: Circular dependency detected involving table '{}'. Falling back to standard order.", node.table_name);
+                    return Ok(tables);
+                }
+            },
+        };
+
+        // 4. Break the cycle by deferring one FK and sort again
+        let (table_name, column) = cycle_edge;
+        status!("⚠️ Circular dependency: deferring {}.{} (filled in by UPDATE after all inserts)", table_name, column);
+        let fk = tables.iter_mut()
+            .filter(|t| t.qualified_name() == table_name)
+            .flat_map(|t| t.foreign_keys.iter_mut())
+            .find(|fk| fk.column == column && !fk.deferred);
+        match fk {
+            Some(fk) => fk.deferred = true,
+            None => return Ok(tables),
         }
     }
-}
\ No newline at end of file
+}
+
+/// Picks an FK inside a strongly connected component to defer, as (child table, column).
+/// Nullable FKs are preferred: they can be inserted as NULL, while NOT NULL ones need the
+/// constraint to be DEFERRABLE for the placeholder value to be accepted. Inferred FKs have
+/// no constraint at all, so they rank with the deferrable ones.
+fn pick_cycle_edge(graph: &DiGraph<&Table, &str>) -> Option<(String, String)> {
+    let component: HashSet<NodeIndex> = tarjan_scc(graph).into_iter().find(|scc| scc.len() > 1)?.into_iter().collect();
+    let candidates: Vec<_> = graph.edge_references()
+        .filter(|e| component.contains(&e.source()) && component.contains(&e.target()))
+        .collect();
+    let is_nullable = |e: &petgraph::graph::EdgeReference<&str>| {
+        graph[e.target()].columns.iter().any(|c| c.name == *e.weight() && c.is_nullable)
+    };
+    let is_deferrable = |e: &petgraph::graph::EdgeReference<&str>| {
+        graph[e.target()].foreign_keys.iter().any(|fk| fk.column == *e.weight() && (fk.deferrable || fk.inferred))
+    };
+    let edge = candidates.iter().find(|e| is_nullable(e))
+        .or_else(|| candidates.iter().find(|e| is_deferrable(e)))
+        .or(candidates.first())?;
+    if !is_nullable(edge) && !is_deferrable(edge) {
+        status!("⚠️ {}.{} is NOT NULL and NOT DEFERRABLE: its placeholder values will be rejected on import",
+            graph[edge.target()].table_name, edge.weight());
+    }
+    Some((graph[edge.target()].qualified_name(), edge.weight().to_string()))
+}
+
+/// Prints how the FK graph splits into connected components and which tables stand alone.
+pub fn report_components(tables: &[Table]) {
+    let graph = dependency_graph(tables, false);
+    let isolated: Vec<String> = graph.node_indices()
+        .filter(|&idx| graph.neighbors_undirected(idx).next().is_none())
+        .map(|idx| graph[idx].qualified_name())
+        .collect();
+
+    status!("🔗 FK graph: {} connected component(s), {} isolated table(s)", connected_components(&graph), isolated.len());
+    if !isolated.is_empty() {
+        status!("   Isolated: {}", isolated.join(", "));
+    }
+}
+
+/// Writes the dependency DAG in Graphviz DOT format, labelling each edge with its FK column.
+pub fn write_graphviz(tables: &[Table], path: &str) -> Result<()> {
+    let graph = dependency_graph(tables, true)
+        .map(|_, table| table.qualified_name(), |_, column| column.to_string());
+    fs::write(path, format!("{}", Dot::new(&graph)))?;
+    Ok(())
+}
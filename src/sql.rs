@@ -0,0 +1,132 @@
+// ====================================================================================
+// SQL LITERAL & IDENTIFIER HELPERS
+// ====================================================================================
+
+/// Quotes an identifier for Postgres so reserved words (`user`, `order`, ...) stay valid.
+/// A name with control characters becomes a `U&"..."` identifier with `\XXXX` escapes, so
+/// every statement keeps to the lines the dump's layout gives it.
+pub fn quote_ident(name: &str) -> String {
+    if !name.chars().any(|c| c.is_control()) {
+        return format!("\"{}\"", name.replace('"', "\"\""));
+    }
+
+    let mut escaped = String::with_capacity(name.len() + 4);
+    escaped.push_str("U&\"");
+    for c in name.chars() {
+        match c {
+            '"' => escaped.push_str("\"\""),
+            '\\' => escaped.push_str("\\\\"),
+            c if c.is_control() => escaped.push_str(&format!("\\{:04X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+/// Makes a name safe to put in a `--` comment: control characters are shown escaped (`\n`)
+/// instead of ending the comment early.
+pub fn comment_text(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_control() { c.escape_default().to_string() } else { c.to_string() })
+        .collect()
+}
+
+/// Quotes a table reference, qualifying it with its schema unless it lives in `public`.
+pub fn quote_table(schema: &str, table: &str) -> String {
+    if schema.is_empty() || schema == "public" {
+        quote_ident(table)
+    } else {
+        format!("{}.{}", quote_ident(schema), quote_ident(table))
+    }
+}
+
+/// Quotes text as a Postgres string literal. Plain text becomes `'...'`; text containing
+/// backslashes or control characters becomes an `E'...'` escape string, which reads the same
+/// whatever `standard_conforming_strings` is set to. NUL can't be stored in text and is dropped.
+pub fn escape_sql_string(value: &str) -> String {
+    if !value.chars().any(|c| c == '\\' || c.is_control()) {
+        return format!("'{}'", value.replace('\'', "''"));
+    }
+
+    let mut escaped = String::with_capacity(value.len() + 3);
+    escaped.push_str("E'");
+    for c in value.chars() {
+        match c {
+            '\'' => escaped.push_str("''"),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\0' => {}
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04X}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('\'');
+    escaped
+}
+
+/// Writes bytes (as hex digits) as a `bytea` literal. The `\x` prefix goes through
+/// `escape_sql_string`, so the value survives `standard_conforming_strings = off`.
+pub fn bytea_literal(hex: &str) -> String {
+    format!("{}::bytea", escape_sql_string(&format!("\\x{}", hex)))
+}
+
+/// Turns a generated SQL literal back into its raw value; `None` for `NULL`.
+/// Understands both `'...'` and the `E'...'` form written by `escape_sql_string`, with or
+/// without the `::bytea` cast `bytea_literal` adds.
+pub fn sql_literal_to_raw(literal: &str) -> Option<String> {
+    if literal == "NULL" {
+        return None;
+    }
+    let literal = literal.strip_suffix("::bytea").unwrap_or(literal);
+    if let Some(inner) = literal.strip_prefix("E'").and_then(|l| l.strip_suffix('\'')) {
+        return Some(unescape_e_string(inner));
+    }
+    match literal.strip_prefix('\'').and_then(|l| l.strip_suffix('\'')) {
+        Some(inner) => Some(inner.replace("''", "'")),
+        None => Some(literal.to_string()),
+    }
+}
+
+/// Reverses the escapes `escape_sql_string` emits inside `E'...'`.
+fn unescape_e_string(inner: &str) -> String {
+    let mut raw = String::with_capacity(inner.len());
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                raw.push('\'');
+            }
+            '\\' => match chars.next() {
+                Some('n') => raw.push('\n'),
+                Some('r') => raw.push('\r'),
+                Some('t') => raw.push('\t'),
+                Some('u') => {
+                    let hex: String = chars.by_ref().take(4).collect();
+                    if let Some(decoded) = u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                        raw.push(decoded);
+                    }
+                }
+                Some(other) => raw.push(other),
+                None => raw.push('\\'),
+            },
+            c => raw.push(c),
+        }
+    }
+    raw
+}
+
+/// Formats a value as an RFC 4180 CSV field. `None` (NULL) becomes an empty unquoted field,
+/// while an empty string is written as `""` so the two stay distinguishable.
+pub fn csv_field(value: Option<&str>) -> String {
+    match value {
+        None => String::new(),
+        Some(v) if v.is_empty() || v.contains([',', '"', '\n', '\r']) => {
+            format!("\"{}\"", v.replace('"', "\"\""))
+        }
+        Some(v) => v.to_string(),
+    }
+}
@@ -0,0 +1,55 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// ====================================================================================
+// TERMINAL OUTPUT
+// ====================================================================================
+
+static QUIET: AtomicBool = AtomicBool::new(false);
+static TO_STDERR: AtomicBool = AtomicBool::new(false);
+
+/// Silences every `status!` line and progress bar for the rest of the run.
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Sends `status!` lines to stderr, keeping stdout clean for a dump written there.
+pub fn set_stderr(to_stderr: bool) {
+    TO_STDERR.store(to_stderr, Ordering::Relaxed);
+}
+
+pub fn is_stderr() -> bool {
+    TO_STDERR.load(Ordering::Relaxed)
+}
+
+/// `println!` for human-facing progress chatter; suppressed under `--quiet` and moved to
+/// stderr when the dump itself goes to stdout.
+#[macro_export]
+macro_rules! status {
+    ($($arg:tt)*) => {
+        if $crate::ui::is_quiet() {
+        } else if $crate::ui::is_stderr() {
+            eprintln!($($arg)*);
+        } else {
+            println!($($arg)*);
+        }
+    };
+}
+
+/// Row-level progress bar with ETA, drawn on stderr; hidden under `--quiet`.
+pub fn progress_bar(total: u64) -> ProgressBar {
+    if is_quiet() {
+        return ProgressBar::hidden();
+    }
+    let bar = ProgressBar::new(total);
+    if let Ok(style) = ProgressStyle::with_template(
+        "{spinner:.green} [{elapsed_precise}] [{wide_bar:.cyan/blue}] {pos}/{len} rows ({eta} left) {msg}",
+    ) {
+        bar.set_style(style.progress_chars("=>-"));
+    }
+    bar
+}
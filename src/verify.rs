@@ -0,0 +1,151 @@
+use crate::schema::Table;
+use crate::sql::quote_ident;
+use anyhow::{bail, Context, Result};
+use sqlx::{Executor, PgPool};
+use std::collections::BTreeSet;
+
+// ====================================================================================
+// DUMP VERIFICATION
+// ====================================================================================
+
+/// Scratch schema that stands in for `public` while a dump is replayed; other schemas get
+/// `synthdb_verify_<schema>`.
+pub const VERIFY_SCHEMA: &str = "synthdb_verify";
+
+/// Longest excerpt of a failing statement shown in the error.
+const STATEMENT_EXCERPT_LEN: usize = 300;
+
+/// Replays a finished SQL dump against scratch copies of `tables` and drops them again,
+/// returning the number of statements applied. Fails with the first statement Postgres
+/// rejected.
+///
+/// With `create_tables` (a data-only dump) the scratch tables are built with
+/// `CREATE TABLE ... (LIKE ... INCLUDING ALL)`, so checks, unique indexes and defaults match
+/// the source; foreign keys are added on top. Otherwise the dump's own DDL creates them.
+pub async fn verify_dump(pool: &PgPool, tables: &[Table], dump: &str, create_tables: bool) -> Result<usize> {
+    let schemas: BTreeSet<&str> = tables.iter().map(|t| t.schema_name.as_str()).collect();
+    let scratch: Vec<String> = schemas.iter().map(|s| scratch_schema(s)).collect();
+
+    // One connection throughout: the search_path and the dump's transaction live on it.
+    let mut conn = pool.acquire().await?;
+    drop_schemas(&mut conn, &scratch).await?;
+
+    let result = async {
+        for schema in &scratch {
+            conn.execute(format!("CREATE SCHEMA {}", quote_ident(schema)).as_str()).await
+                .with_context(|| format!("could not create the verification schema {}", schema))?;
+        }
+        conn.execute(format!("SET search_path TO {}, public", quote_ident(VERIFY_SCHEMA)).as_str()).await?;
+
+        if create_tables {
+            for statement in scratch_tables(tables) {
+                conn.execute(statement.as_str()).await
+                    .with_context(|| format!("could not prepare verification tables: {}", statement))?;
+            }
+        }
+
+        let statements = split_statements(dump);
+        for (i, statement) in statements.iter().enumerate() {
+            let statement = remap_schemas(statement, &schemas);
+            if let Err(e) = conn.execute(statement.as_str()).await {
+                bail!("statement {} of {} failed: {}\n    {}", i + 1, statements.len(), e, excerpt(&statement));
+            }
+        }
+        Ok(statements.len())
+    }.await;
+
+    // A failure leaves the dump's transaction open; close it before cleaning up.
+    let _ = conn.execute("ROLLBACK").await;
+    let _ = conn.execute("RESET search_path").await;
+    drop_schemas(&mut conn, &scratch).await?;
+    result
+}
+
+fn scratch_schema(schema: &str) -> String {
+    if schema == "public" {
+        VERIFY_SCHEMA.to_string()
+    } else {
+        format!("{}_{}", VERIFY_SCHEMA, schema)
+    }
+}
+
+async fn drop_schemas(conn: &mut sqlx::pool::PoolConnection<sqlx::Postgres>, schemas: &[String]) -> Result<()> {
+    for schema in schemas {
+        conn.execute(format!("DROP SCHEMA IF EXISTS {} CASCADE", quote_ident(schema)).as_str()).await
+            .with_context(|| format!("could not drop the verification schema {}", schema))?;
+    }
+    Ok(())
+}
+
+/// `CREATE TABLE ... LIKE` for every table, then the foreign keys between the copies.
+fn scratch_tables(tables: &[Table]) -> Vec<String> {
+    let mut statements: Vec<String> = tables.iter()
+        .map(|t| format!(
+            "CREATE TABLE {}.{} (LIKE {} INCLUDING ALL)",
+            quote_ident(&scratch_schema(&t.schema_name)),
+            quote_ident(&t.table_name),
+            format_args!("{}.{}", quote_ident(&t.schema_name), quote_ident(&t.table_name)),
+        ))
+        .collect();
+    let names: BTreeSet<String> = tables.iter().map(|t| t.qualified_name()).collect();
+    for table in tables {
+        // Inferred FKs have no constraint in the source either, and a parent left out of the
+        // dump has no scratch copy to point at.
+        for fk in table.foreign_keys.iter().filter(|fk| !fk.inferred && names.contains(&fk.ref_qualified_name())) {
+            statements.push(format!(
+                "ALTER TABLE {}.{} ADD FOREIGN KEY ({}) REFERENCES {}.{} ({}){}",
+                quote_ident(&scratch_schema(&table.schema_name)),
+                quote_ident(&table.table_name),
+                quote_ident(&fk.column),
+                quote_ident(&scratch_schema(&fk.ref_schema)),
+                quote_ident(&fk.ref_table),
+                quote_ident(&fk.ref_column),
+                if fk.deferrable { " DEFERRABLE" } else { "" },
+            ));
+        }
+    }
+    statements
+}
+
+/// Points schema-qualified references in the dump at the scratch schemas. `public` tables are
+/// written unqualified and reach the scratch copies through the search_path.
+fn remap_schemas(statement: &str, schemas: &BTreeSet<&str>) -> String {
+    let mut statement = statement.to_string();
+    for schema in schemas.iter().filter(|s| **s != "public") {
+        let qualified = format!("{}.", quote_ident(schema));
+        statement = statement.replace(&qualified, &format!("{}.", quote_ident(&scratch_schema(schema))));
+        let created = format!("CREATE SCHEMA IF NOT EXISTS {}", quote_ident(schema));
+        statement = statement.replace(&created, &format!("CREATE SCHEMA IF NOT EXISTS {}", quote_ident(&scratch_schema(schema))));
+    }
+    statement
+}
+
+/// Splits a dump into statements. Every statement the generator writes ends its last line
+/// with `;`, and literals never span lines (control characters are escaped), so line
+/// endings are enough to find the boundaries.
+fn split_statements(dump: &str) -> Vec<String> {
+    let mut statements = Vec::new();
+    let mut current = String::new();
+    for line in dump.lines() {
+        if current.is_empty() && (line.trim().is_empty() || line.starts_with("--")) {
+            continue;
+        }
+        current.push_str(line);
+        current.push('\n');
+        if line.trim_end().ends_with(';') {
+            statements.push(std::mem::take(&mut current));
+        }
+    }
+    if !current.trim().is_empty() {
+        statements.push(current);
+    }
+    statements
+}
+
+fn excerpt(statement: &str) -> String {
+    let statement = statement.trim();
+    match statement.char_indices().nth(STATEMENT_EXCERPT_LEN) {
+        Some((cut, _)) => format!("{}...", &statement[..cut]),
+        None => statement.to_string(),
+    }
+}